@@ -26,6 +26,33 @@ pub struct FmtCommand {
     /// Display a detailed comparison if formatting issues are detected
     #[arg(long, action, requires = "check")]
     pub show_diff: bool,
+    /// Line endings of formatted files. One of: auto, lf, crlf. "auto" keeps the dominant line ending of each file
+    #[arg(long)]
+    pub line_endings: Option<String>,
+    /// Read the document from stdin and print the formatted document to stdout
+    #[arg(long, action, conflicts_with = "check")]
+    pub stdin: bool,
+    /// Path of the document read from stdin. Used to find config and to check whether file is excluded
+    #[arg(long, requires = "stdin", value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub stdin_filepath: Option<String>,
+    /// Only reformat top-level blocks that intersect with given lines, e.g. "10:20". Lines start at 1. Requires --stdin or a single file
+    #[arg(long, conflicts_with = "check", value_name = "START:END", value_parser = parse_line_range)]
+    pub range: Option<std::ops::RangeInclusive<usize>>,
+}
+
+/// Parse range of lines in a format "<start-line>:<end-line>"
+fn parse_line_range(range: &str) -> Result<std::ops::RangeInclusive<usize>, String> {
+    let (start, end) = range
+        .split_once(':')
+        .ok_or(String::from("expected <start-line>:<end-line>"))?;
+    let start = start.trim().parse::<usize>().map_err(|e| e.to_string())?;
+    let end = end.trim().parse::<usize>().map_err(|e| e.to_string())?;
+    if start == 0 || start > end {
+        return Err(String::from(
+            "start line must be greater than 0 and not greater than end line",
+        ));
+    }
+    Ok(start..=end)
 }
 
 #[derive(Debug, clap::Parser)]
@@ -97,6 +124,60 @@ pub struct LinkcheckCommand {
     /// Allow insecure SSL certificates. Use only as a last resort because it is insecure
     #[arg(long, short, action)]
     pub allow_insecure: bool,
+    /// Check only local files, directories, images and fragments of local Markdown files without network access
+    #[arg(long, action)]
+    pub offline: bool,
+    /// List external URLs that were skipped in offline mode
+    #[arg(long, action)]
+    pub list_skipped: bool,
+    /// Fetch HTML pages of external URLs with fragments and check that fragments exist on them
+    #[arg(long, action)]
+    pub remote_fragments: bool,
+    /// Directory against which root-relative links(e.g. "/docs/api.md") are resolved. Defaults to the project root
+    #[arg(long, value_name = "DIR_PATH", value_hint=clap::ValueHint::DirPath)]
+    pub root_dir: Option<String>,
+    /// URL against which root-relative links are resolved instead of the root dir, e.g. where the documentation is published
+    #[arg(long)]
+    pub base_url: Option<String>,
+    /// Proxy for HTTP and HTTPS requests, e.g. "http://proxy.example.com:8080"
+    #[arg(long)]
+    pub proxy: Option<String>,
+    /// Hosts that are requested without the proxy, e.g. "localhost" or ".example.com"
+    #[arg(long)]
+    pub no_proxy: Vec<String>,
+    /// PEM file with root certificates trusted in addition to the system ones
+    #[arg(long, value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub ca_cert: Vec<String>,
+    /// PEM file with the client certificate for mutual TLS
+    #[arg(long, value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub client_cert: Option<String>,
+    /// PEM file with the PKCS#8 private key of the client certificate, when it is not in the certificate file
+    #[arg(long, value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub client_key: Option<String>,
+    /// Cookie jar file in Netscape format. Cookies from it are sent with requests to their domains
+    #[arg(long, value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub cookie_jar: Option<String>,
+    /// Maximum number of concurrent requests
+    #[arg(long)]
+    pub max_concurrency: Option<u16>,
+    /// Maximum number of concurrent requests to the same host
+    #[arg(long)]
+    pub max_concurrency_per_host: Option<u16>,
+    /// Do not read or write results of previous runs
+    #[arg(long, action, conflicts_with = "refresh")]
+    pub no_cache: bool,
+    /// Ignore results of previous runs and check every link again. Cache is updated with new results
+    #[arg(long, action)]
+    pub refresh: bool,
+    /// Replace permanently redirected links with their final URL
+    #[arg(long, action)]
+    pub fix_redirects: bool,
+    /// Save every unique link with its kind, status and references to a file. Format is CSV when file has ".csv" extension, JSON otherwise
+    #[arg(long, value_name = "FILE_PATH", value_hint=clap::ValueHint::FilePath)]
+    pub inventory: Option<String>,
+    /// Only collect links into the inventory without checking them
+    #[arg(long, action, requires = "inventory")]
+    pub collect_only: bool,
 }
 
 #[derive(Debug, clap::Parser)]
@@ -124,7 +205,7 @@ pub enum Subcommands {
     /// Formats Markdown files. This will fix common formatting issues such as trailing whitespace, inconsistent line endings, and more
     Fmt(FmtCommand),
     /// Checks the Markdown document for broken links(both web and local)
-    Linkcheck(LinkcheckCommand),
+    Linkcheck(Box<LinkcheckCommand>),
     /// Checks document for common Markdown linting issues
     Lint(LintCommand),
     /// Reviews the document using OpenAI's API. Requires internet connection and OPEN_AI_API_KEY environment variable(.dotenv file is supported)
@@ -16,6 +16,9 @@ pub fn read_config(cli: &crate::cli::Cli) -> common::Config {
         );
         if let Some(cfg) = common::Config::from_file(cfg_path_from_cli) {
             config = cfg; // Replace default config with config from file
+            config.location = dunce::canonicalize(cfg_path_from_cli)
+                .ok()
+                .map(|path| path.display().to_string()); // Remember where we found it
         } else {
             log::warn!("Config file not found in {}", &cfg_path_from_cli);
         }
@@ -32,23 +35,53 @@ pub fn read_config(cli: &crate::cli::Cli) -> common::Config {
             ".github/checkmark.toml",
             ".github/.checkmark.toml",
         ];
+        // Document read from stdin has no project root, so look for
+        // the config next to it and in all of its parent dirs
+        let mut search_dirs: Vec<std::path::PathBuf> = vec![];
+        if let crate::cli::Subcommands::Fmt(crate::cli::FmtCommand {
+            stdin_filepath: Some(stdin_filepath),
+            ..
+        }) = &cli.subcommands
+        {
+            if let Ok(stdin_filepath) = std::path::absolute(stdin_filepath) {
+                search_dirs.extend(stdin_filepath.ancestors().skip(1).map(|p| p.to_path_buf()));
+            }
+        }
+        search_dirs.push(std::path::PathBuf::new()); // Current dir
         log::debug!(
-            "Trying to read config from default locations {:#?}...",
-            &default_locations
+            "Trying to read config from default locations {:#?} in {:#?}...",
+            &default_locations,
+            &search_dirs
         );
-        for file_path in default_locations.iter() {
-            if let Some(cfg) = common::Config::from_file(file_path) {
-                config = cfg; // Replace default config with config from file
-                config.location = Some(
-                    dunce::canonicalize(file_path)
-                        .unwrap()
-                        .display()
-                        .to_string(),
-                ); // Remember where we found it
-                break;
+        'search: for dir in &search_dirs {
+            for file_path in default_locations.iter() {
+                let file_path = dir.join(file_path).display().to_string();
+                if let Some(cfg) = common::Config::from_file(&file_path) {
+                    config = cfg; // Replace default config with config from file
+                    config.location = Some(
+                        dunce::canonicalize(&file_path)
+                            .unwrap()
+                            .display()
+                            .to_string(),
+                    ); // Remember where we found it
+                    break 'search;
+                }
             }
         }
     }
+    // Paths in the config file are relative to the file, not to the current dir
+    if let Some(config_dir) = config
+        .location
+        .as_ref()
+        .and_then(|location| std::path::Path::new(location).parent())
+    {
+        if let Some(root_dir) = &config.link_checker.root_dir {
+            config.link_checker.root_dir = Some(config_dir.join(root_dir).display().to_string());
+        }
+        for dictionary in config.spelling.dictionaries.iter_mut() {
+            dictionary.path = config_dir.join(&dictionary.path).display().to_string();
+        }
+    }
     log::debug!("Config after merging with file: {:#?}", &config);
 
     log::debug!("Merging config with CLI options...");
@@ -63,6 +96,14 @@ pub fn read_config(cli: &crate::cli::Cli) -> common::Config {
             if fmt.show_diff && !config.fmt.show_diff {
                 config.fmt.show_diff = true;
             }
+            if let Some(line_endings) = &fmt.line_endings {
+                match line_endings.to_lowercase().as_str() {
+                    "auto" => config.fmt.line_endings = common::LineEndings::Auto,
+                    "lf" => config.fmt.line_endings = common::LineEndings::Lf,
+                    "crlf" => config.fmt.line_endings = common::LineEndings::Crlf,
+                    _ => log::warn!("Unknown line endings value: {line_endings}! Must be one of: auto, lf, crlf. Ignoring this setting"),
+                }
+            }
         }
         crate::cli::Subcommands::Linkcheck(links) => {
             if !links.ignore_wildcards.is_empty() {
@@ -87,6 +128,61 @@ pub fn read_config(cli: &crate::cli::Cli) -> common::Config {
             if links.allow_insecure && !config.link_checker.allow_insecure {
                 config.link_checker.allow_insecure = true;
             }
+            if links.offline && !config.link_checker.offline {
+                config.link_checker.offline = true;
+            }
+            if links.list_skipped && !config.link_checker.list_skipped {
+                config.link_checker.list_skipped = true;
+            }
+            if links.remote_fragments && !config.link_checker.remote_fragments {
+                config.link_checker.remote_fragments = true;
+            }
+            if let Some(root_dir) = &links.root_dir {
+                config.link_checker.root_dir = Some(root_dir.clone());
+            }
+            // Root-relative links point into the project that is being checked
+            if config.link_checker.root_dir.is_none() {
+                let root_dir = checkmark_link_checker::project_root_dir(std::path::Path::new(
+                    &cli.project_root,
+                ));
+                config.link_checker.root_dir = Some(root_dir.display().to_string());
+            }
+            if let Some(base_url) = &links.base_url {
+                config.link_checker.base_url = Some(base_url.clone());
+            }
+            if let Some(proxy) = &links.proxy {
+                config.link_checker.network.proxy = Some(proxy.clone());
+            }
+            if !links.no_proxy.is_empty() {
+                config.link_checker.network.no_proxy = links.no_proxy.clone();
+            }
+            if !links.ca_cert.is_empty() {
+                config.link_checker.network.ca_certs = links.ca_cert.clone();
+            }
+            if let Some(client_cert) = &links.client_cert {
+                config.link_checker.network.client_cert = Some(client_cert.clone());
+            }
+            if let Some(client_key) = &links.client_key {
+                config.link_checker.network.client_key = Some(client_key.clone());
+            }
+            if let Some(cookie_jar) = &links.cookie_jar {
+                config.link_checker.cookie_jar = Some(cookie_jar.clone());
+            }
+            if let Some(max_concurrency) = links.max_concurrency {
+                config.link_checker.max_concurrency = Some(max_concurrency);
+            }
+            if let Some(max_concurrency_per_host) = links.max_concurrency_per_host {
+                config.link_checker.max_concurrency_per_host = Some(max_concurrency_per_host);
+            }
+            if links.no_cache {
+                config.link_checker.cache.enabled = false;
+            }
+            if links.refresh {
+                config.link_checker.cache.refresh = true;
+            }
+            if links.fix_redirects {
+                config.link_checker.redirects.fix = true;
+            }
         }
         crate::cli::Subcommands::Lint(_) => {}
         crate::cli::Subcommands::Review(review) => {
@@ -17,6 +17,64 @@ fn has_any_critical_issue(files: &Vec<common::MarkDownFile>) -> bool {
     any_critical_issue
 }
 
+/// Format document from stdin and print it to stdout.
+/// Document is printed as it is when its path is excluded
+fn fmt_stdin(fmt: &cli::FmtCommand, config: &common::Config) -> Result<(), errors::AppError> {
+    use std::io::{Read, Write};
+
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| errors::AppError {
+            message: format!("Unable to read stdin: {e}"),
+        })?;
+    let path = match &fmt.stdin_filepath {
+        Some(path) => std::path::absolute(path)
+            .map(|p| p.display().to_string())
+            .unwrap_or(path.clone()),
+        None => String::from("stdin"),
+    };
+    let file = common::MarkDownFile {
+        path,
+        content,
+        issues: vec![],
+    };
+    let formatted = if checkmark_ls::is_excluded(&file.path, &config.global.exclude) {
+        file.clone()
+    } else {
+        match &fmt.range {
+            Some(lines) => checkmark_fmt::fmt_markdown_range(&file, config, lines),
+            None => checkmark_fmt::fmt_markdown(&file, config),
+        }
+    };
+    // Stdout is reserved for the document
+    for issue in &formatted.issues {
+        log::warn!(
+            "{}:{} {}",
+            &issue.file_path,
+            issue.row_num_start,
+            &issue.message
+        );
+    }
+    std::io::stdout()
+        .write_all(formatted.content.as_bytes())
+        .map_err(|e| errors::AppError {
+            message: format!("Unable to write to stdout: {e}"),
+        })
+}
+
+/// Save inventory of links as CSV when file has ".csv" extension, as JSON otherwise
+fn save_inventory(path: &str, inventory: &[checkmark_link_checker::InventoryEntry]) {
+    let is_csv = std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let content = match is_csv {
+        true => checkmark_link_checker::to_csv(inventory),
+        false => checkmark_link_checker::to_json(inventory),
+    };
+    std::fs::write(path, content).unwrap();
+}
+
 #[tokio::main]
 async fn main() -> Result<(), errors::AppError> {
     // Parse CLI arguments
@@ -36,15 +94,39 @@ async fn main() -> Result<(), errors::AppError> {
     // Read config
     let config = config::read_config(&cli);
 
+    // Document from stdin is printed to stdout, so no TUI and no files
+    if let cli::Subcommands::Fmt(fmt) = &cli.subcommands {
+        if fmt.stdin {
+            return fmt_stdin(fmt, &config);
+        }
+        // Lines of one document mean nothing for the others
+        if fmt.range.is_some() && !std::path::Path::new(&cli.project_root).is_file() {
+            return Err(errors::AppError {
+                message: String::from(
+                    "--range can only be used with --stdin or with a single file as the project root",
+                ),
+            });
+        }
+    }
+
     // Create TUI
     let tui = common::tui::CheckProgressTUI::new_thread_safe(cli.ci);
 
     // Read all MD files
-    let mut files = checkmark_ls::ls(&cli.project_root, &config.global.exclude, &tui).await;
+    let mut files = checkmark_ls::ls(
+        &cli.project_root,
+        &config.global.exclude,
+        &config.link_checker.network,
+        &tui,
+    )
+    .await;
+
+    // Issues that do not point into any of the files
+    let mut detached_issues: Vec<common::CheckIssue> = vec![];
 
     // Analyze
     match &cli.subcommands {
-        cli::Subcommands::Fmt(_) => match config.fmt.check {
+        cli::Subcommands::Fmt(fmt) => match config.fmt.check {
             true => {
                 tui.lock().unwrap().start_spinner("Checking format...");
                 files.par_iter_mut().for_each(|file| {
@@ -62,11 +144,12 @@ async fn main() -> Result<(), errors::AppError> {
                         .to_string(),
                 );
                 files.par_iter_mut().for_each(|file| {
-                    std::fs::write(
-                        &file.path,
-                        checkmark_fmt::fmt_markdown(file, &config).content,
-                    )
-                    .unwrap();
+                    let mut formatted = match &fmt.range {
+                        Some(lines) => checkmark_fmt::fmt_markdown_range(file, &config, lines),
+                        None => checkmark_fmt::fmt_markdown(file, &config),
+                    };
+                    std::fs::write(&file.path, &formatted.content).unwrap();
+                    file.issues.append(&mut formatted.issues);
                     tui.lock().unwrap().print_file_check_status(file);
                 });
             }
@@ -124,14 +207,30 @@ async fn main() -> Result<(), errors::AppError> {
                     issues: vec![],
                 });
         }
-        cli::Subcommands::Linkcheck(_) => {
+        cli::Subcommands::Linkcheck(links) if links.collect_only => {
+            tui.lock().unwrap().start_spinner("Collecting links...");
+            let inventory = checkmark_link_checker::collect_inventory(&files, &config).await;
+            save_inventory(links.inventory.as_ref().unwrap(), &inventory);
+        }
+        cli::Subcommands::Linkcheck(links) => {
             tui.lock().unwrap().start_spinner("Checking links...");
-            for result in checkmark_link_checker::bulk_check(&files, &config).await {
+            let report = checkmark_link_checker::bulk_check_with_inventory(&files, &config).await;
+            if let Some(inventory_path) = &links.inventory {
+                save_inventory(inventory_path, &report.inventory);
+            }
+            // Unused assets are not documents, so their issues are reported separately
+            detached_issues.extend(report.unused_assets.into_iter().map(|asset| asset.issue));
+            for result in report.results {
                 let file = files
                     .iter_mut()
                     .find(|file| file.path.eq(&result.path))
                     .unwrap();
                 file.issues.append(result.issues.clone().as_mut());
+                if !result.fixes.is_empty() {
+                    file.content =
+                        checkmark_link_checker::apply_fixes(&file.content, &result.fixes);
+                    std::fs::write(&file.path, &file.content).unwrap();
+                }
                 tui.lock().unwrap().print_file_check_status(file);
             }
         }
@@ -157,6 +256,7 @@ async fn main() -> Result<(), errors::AppError> {
 
     // Print all collected check issues
     tui.lock().unwrap().print_report(&files);
+    tui.lock().unwrap().print_issues(&detached_issues);
 
     // When requested - generate SARIF json
     if let Some(file_path) = &cli.sarif {
@@ -180,6 +280,7 @@ async fn main() -> Result<(), errors::AppError> {
                     .collect(),
             );
         }
+        results.extend(detached_issues.iter().map(|issue| issue.to_sarif_result()));
 
         let runs = serde_sarif::sarif::RunBuilder::default()
             .tool(tool)
@@ -0,0 +1,357 @@
+use colored::Colorize;
+use markdown::mdast::{Code, Node};
+use toml_edit::{Array, Item, Table, Value};
+
+/// Walks through the AST and formats content of every fenced code block
+/// which language is supported either by built-in formatters(JSON, TOML)
+/// or by an external formatter from the config.
+/// Only code blocks for which `is_formatted` returns true are formatted.
+/// Code blocks that failed to format are left untouched and reported as issues.
+/// AST may be built from the normalized content(no BOM, LF line endings, updated TOC),
+/// so issues are reported at positions of the same code blocks in the original file
+pub fn fmt_code_blocks(
+    ast: &mut Node,
+    file: &common::MarkDownFile,
+    config: &common::Config,
+    is_formatted: impl Fn(&Code) -> bool,
+) -> Vec<common::CheckIssue> {
+    let mut code_blocks: Vec<&mut Code> = vec![];
+    collect_code_blocks(ast, &mut code_blocks);
+    let errors = code_blocks
+        .into_iter()
+        .enumerate()
+        .filter(|(_, code)| is_formatted(code))
+        .filter_map(|(index, code)| fmt_code_block(code, config).err().map(|e| (index, e)))
+        .collect::<Vec<(usize, String)>>();
+    if errors.is_empty() {
+        return vec![];
+    }
+    let (bom, content) = match file.content.strip_prefix('\u{FEFF}') {
+        Some(content) => ('\u{FEFF}'.len_utf8(), content),
+        None => (0, file.content.as_str()),
+    };
+    let mut original = common::ast::parse(content).unwrap();
+    let mut original_code_blocks: Vec<&mut Code> = vec![];
+    collect_code_blocks(&mut original, &mut original_code_blocks);
+    errors
+        .into_iter()
+        .filter_map(|(index, error)| {
+            let code = original_code_blocks.get(index)?;
+            Some(to_issue(code, &error, file, bom))
+        })
+        .collect()
+}
+
+/// Code blocks in the order they appear in the document
+fn collect_code_blocks<'a>(node: &'a mut Node, code_blocks: &mut Vec<&'a mut Code>) {
+    match node {
+        Node::Code(code) => code_blocks.push(code),
+        node => {
+            if let Some(children) = node.children_mut() {
+                for child in children {
+                    collect_code_blocks(child, code_blocks);
+                }
+            }
+        }
+    }
+}
+
+/// Formats content of the code block in-place.
+/// Returns an error description when formatter has failed
+fn fmt_code_block(code: &mut Code, config: &common::Config) -> Result<(), String> {
+    let lang = match &code.lang {
+        Some(lang) => lang.to_lowercase(),
+        None => return Ok(()),
+    };
+    let formatted = if let Some(command) = config.fmt.code_blocks.get(&lang) {
+        log::debug!("Formatting {lang} code block with {command:#?}");
+        fmt_with_external_formatter(&code.value, command)?
+    } else if config.fmt.format_code_blocks && lang == "json" {
+        log::debug!("Formatting JSON code block");
+        fmt_json(&code.value)?
+    } else if config.fmt.format_code_blocks && lang == "toml" {
+        log::debug!("Formatting TOML code block");
+        fmt_toml(&code.value)?
+    } else {
+        return Ok(());
+    };
+    code.value = formatted.trim_end().to_string();
+    Ok(())
+}
+
+/// Pretty-prints JSON with 2 spaces indentation.
+/// Only whitespace between tokens is changed, so the order of keys
+/// and the exact spelling of numbers(`1e3`, `1.50`, big integers) are preserved
+fn fmt_json(source: &str) -> Result<String, String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(source).map_err(|e| e.to_string())?;
+    let mut result = String::new();
+    let mut depth = 0;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                result.push(c);
+                while let Some(c) = chars.next() {
+                    result.push(c);
+                    match c {
+                        '\\' => result.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '{' | '[' => {
+                result.push(c);
+                while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+                if let Some(close) = chars.next_if(|next| matches!(next, '}' | ']')) {
+                    // Empty objects and arrays stay on one line
+                    result.push(close);
+                } else {
+                    depth += 1;
+                    push_json_line_break(&mut result, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                push_json_line_break(&mut result, depth);
+                result.push(c);
+            }
+            ',' => {
+                result.push(c);
+                push_json_line_break(&mut result, depth);
+            }
+            ':' => result.push_str(": "),
+            c if c.is_ascii_whitespace() => {}
+            c => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+fn push_json_line_break(result: &mut String, depth: usize) {
+    result.push('\n');
+    result.push_str(&"  ".repeat(depth));
+}
+
+/// Normalizes whitespace of the TOML document:
+/// removes indentation, puts single space around "=",
+/// collapses multiple blank lines into one.
+/// Comments and the order of keys are preserved
+fn fmt_toml(source: &str) -> Result<String, String> {
+    let mut document = source
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| e.to_string())?;
+    fmt_toml_table(document.as_table_mut());
+    let trailing = format!("{}\n", document.trailing().as_str().unwrap_or(""));
+    document.set_trailing(fmt_toml_comments(&trailing));
+    Ok(document.to_string().trim().to_string())
+}
+
+fn fmt_toml_table(table: &mut Table) {
+    fmt_toml_decor(table.decor_mut(), fmt_toml_trailing_comment);
+    for (mut key, item) in table.iter_mut() {
+        match item {
+            Item::Value(value) => {
+                fmt_toml_decor(key.leaf_decor_mut(), |_| String::from(" "));
+                fmt_toml_value(value);
+            }
+            Item::Table(table) => {
+                // Header keys like "[ a . b ]" are written without spaces
+                key.leaf_decor_mut().clear();
+                fmt_toml_table(table);
+            }
+            Item::ArrayOfTables(tables) => {
+                key.leaf_decor_mut().clear();
+                for table in tables.iter_mut() {
+                    fmt_toml_table(table);
+                }
+            }
+            Item::None => {}
+        }
+    }
+}
+
+fn fmt_toml_value(value: &mut Value) {
+    match value {
+        Value::Array(array) if is_single_line_array(array) => array.fmt(),
+        Value::InlineTable(table) => table.fmt(),
+        _ => {}
+    }
+    let decor = value.decor_mut();
+    let suffix = fmt_toml_trailing_comment(decor.suffix().and_then(|s| s.as_str()).unwrap_or(""));
+    decor.set_prefix(" ");
+    decor.set_suffix(suffix);
+}
+
+/// Normalizes comments before the decorated element and
+/// replaces whatever follows it with the result of fmt_suffix
+fn fmt_toml_decor(decor: &mut toml_edit::Decor, fmt_suffix: fn(&str) -> String) {
+    let prefix = fmt_toml_comments(decor.prefix().and_then(|p| p.as_str()).unwrap_or(""));
+    let suffix = fmt_suffix(decor.suffix().and_then(|s| s.as_str()).unwrap_or(""));
+    decor.set_prefix(prefix);
+    decor.set_suffix(suffix);
+}
+
+/// Multi-line arrays may contain comments between values,
+/// formatting them would put everything on a single line
+fn is_single_line_array(array: &Array) -> bool {
+    !array.to_string().contains('\n')
+}
+
+/// Takes whitespace before a key or a table header and keeps only
+/// comments(without indentation) and a single blank line between them
+fn fmt_toml_comments(prefix: &str) -> String {
+    let mut result = String::new();
+    let mut has_blank_line = false;
+    // Last segment is an indentation of the key itself
+    let lines: Vec<&str> = prefix.split('\n').collect();
+    for line in &lines[..lines.len() - 1] {
+        let line = line.trim();
+        if line.is_empty() {
+            has_blank_line = true;
+        } else {
+            if has_blank_line {
+                result.push('\n');
+                has_blank_line = false;
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    if has_blank_line {
+        result.push('\n');
+    }
+    result
+}
+
+/// Keeps only the comment that follows the value on the same line
+fn fmt_toml_trailing_comment(suffix: &str) -> String {
+    match suffix.trim() {
+        "" => String::new(),
+        comment => format!(" {comment}"),
+    }
+}
+
+/// Pipes the source to stdin of an external command and reads formatted code from its stdout
+fn fmt_with_external_formatter(source: &str, command: &str) -> Result<String, String> {
+    use std::io::Write;
+
+    let args = split_command(command)?;
+    let (program, args) = args
+        .split_first()
+        .ok_or(String::from("Formatter command is empty"))?;
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {command:#?}: {e}"))?;
+    // Written from another thread, otherwise both processes block
+    // once output of the formatter fills the pipe while it is still being fed
+    let mut stdin = child.stdin.take().unwrap();
+    let source = source.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(source.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let written = writer.join().unwrap_or(Ok(()));
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    written.map_err(|e| e.to_string())?;
+    String::from_utf8(output.stdout).map_err(|e| e.to_string())
+}
+
+/// Split command into program and arguments like a POSIX shell does,
+/// e.g. `prettier --stdin-filepath "my file.ts"`. Variables and globs are not expanded
+fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = vec![];
+    let mut arg: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => args.extend(arg.take()),
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err(format!("Unterminated quote in {command:#?}")),
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err(format!("Unterminated quote in {command:#?}")),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err(format!("Unterminated quote in {command:#?}")),
+                    }
+                }
+            }
+            '\\' => {
+                let arg = arg.get_or_insert_with(String::new);
+                arg.extend(chars.next());
+            }
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    Ok(args)
+}
+
+/// Offset of the BOM is added to offsets, since the original AST is parsed without it
+fn to_issue(
+    code: &Code,
+    error: &str,
+    file: &common::MarkDownFile,
+    bom: usize,
+) -> common::CheckIssue {
+    let position = code.position.as_ref().unwrap();
+    common::CheckIssueBuilder::default()
+        .set_category(common::IssueCategory::Formatting)
+        .set_severity(common::IssueSeverity::Warning)
+        .set_file_path(file.path.clone())
+        .set_row_num_start(position.start.line)
+        .set_row_num_end(position.end.line)
+        .set_col_num_start(position.start.column)
+        .set_col_num_end(position.end.column)
+        .set_offset_start(position.start.offset + bom)
+        .set_offset_end(position.end.offset + bom)
+        .set_message(format!(
+            "Failed to format {} code block, left it untouched",
+            code.lang.as_ref().unwrap()
+        ))
+        .push_fix(&format!("💡 {} {error}", "Reason".cyan()))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_split_into_arguments() {
+        assert_eq!(
+            vec![
+                "prettier",
+                "--stdin-filepath",
+                "my file.ts",
+                "it's",
+                "a\\b\"c"
+            ],
+            split_command(r#"prettier  --stdin-filepath "my file.ts" it\'s 'a\b'"\"c""#).unwrap()
+        );
+        assert_eq!(Vec::<String>::new(), split_command("   ").unwrap());
+        assert!(split_command("rustfmt \"--edition").is_err());
+    }
+}
@@ -27,4 +27,6 @@ pub enum Context {
     List(ListContext),
     Blockquote(BlockquoteContext),
     BlockquoteInList(BlockquoteInListContext),
+    /// Inline content of a table cell
+    TableCell,
 }
@@ -1,4 +1,6 @@
+mod code_blocks;
 mod context;
+mod references;
 mod style;
 mod utils;
 
@@ -9,85 +11,103 @@ use utils::*;
 use colored::Colorize;
 use markdown::mdast;
 use markdown::mdast::{AlignKind, Node};
+use unicode_width::UnicodeWidthStr;
 
-/// Takes a table, walks through it's cells, calculates max size of each column
-/// and returns a vector of sizes that represents maximum possible size of each column(per all rows)
-/// Useful to get know what is the expected size of each column to align them later
-fn calculate_max_col_len(table: &mdast::Table, source: &str) -> Vec<usize> {
-    let mut max_col_len = std::collections::BTreeMap::<usize, usize>::new();
-    for child in &table.children {
-        if let Node::TableRow(tr) = child {
-            for (i, child) in tr.children.iter().enumerate() {
-                let offset_start = child.position().as_ref().unwrap().start.offset;
-                let offset_end = child.position().as_ref().unwrap().end.offset;
-                let col_len = source[offset_start..offset_end]
-                    .trim_matches(' ')
-                    .trim_matches('|')
-                    .trim_matches(' ')
-                    .len();
-                if let Some(max_len) = max_col_len.get(&i) {
-                    if col_len > *max_len {
-                        max_col_len.insert(i, col_len);
+/// Renders content of every cell of the table.
+/// Returns rows, each is a vector of rendered cells
+fn render_table_cells(
+    table: &mdast::Table,
+    source: &str,
+    options: &FormattingOptions,
+) -> Vec<Vec<String>> {
+    table
+        .children
+        .iter()
+        .map(|row| {
+            row.children()
+                .unwrap()
+                .iter()
+                .map(|cell| {
+                    let mut buffer = String::new();
+                    for child in cell.children().unwrap() {
+                        to_md(child, &mut buffer, &Context::TableCell, source, options);
                     }
-                } else {
-                    max_col_len.insert(i, col_len);
-                }
-            }
-        }
+                    buffer
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Minimal width of the separator cell that can express the alignment
+fn min_separator_width(align: &AlignKind) -> usize {
+    match align {
+        AlignKind::Left | AlignKind::Right => 2,
+        AlignKind::Center => 3,
+        AlignKind::None => 1,
     }
-    max_col_len.into_values().collect()
 }
 
-fn render_table_row(
-    node: &mdast::Node,
-    buffer: &mut String,
-    context: &Context,
-    source: &str,
-    options: &FormattingOptions,
-    expected_col_lengths: &[usize],
-) {
-    // Render a heading of the table
+/// Takes rendered cells, calculates max width of each column in terminal display columns
+/// and returns a vector of widths that represents maximum possible width of each column(per all rows).
+/// Width is limited by max_column_width, when set, and is never less than separator needs
+/// Useful to get know what is the expected size of each column to align them later
+fn calculate_max_col_width(
+    table: &mdast::Table,
+    rows: &[Vec<String>],
+    max_column_width: Option<usize>,
+) -> Vec<usize> {
+    table
+        .align
+        .iter()
+        .enumerate()
+        .map(|(i, align)| {
+            let width = rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.width())
+                .max()
+                .unwrap_or(0);
+            let width = match max_column_width {
+                Some(max) => width.min(max),
+                None => width,
+            };
+            width.max(min_separator_width(align))
+        })
+        .collect()
+}
+
+fn render_table_row(buffer: &mut String, cells: &[String], expected_col_widths: &[usize]) {
     buffer.push_str("| ");
-    // Rows
-    for (i, child) in node.children().unwrap().iter().enumerate() {
-        // Cols
-        let len_before = buffer.len();
-        for child in child.children().unwrap() {
-            to_md(child, buffer, context, source, options);
-        }
-        let len_after = buffer.len();
-        let expected_len = len_before + expected_col_lengths.get(i).unwrap();
-        if len_after < expected_len {
-            // Fill missing with white spaces
-            buffer.push_str(&" ".repeat(expected_len - len_after));
-        }
+    for (i, cell) in cells.iter().enumerate() {
+        buffer.push_str(cell);
+        // Fill missing with white spaces, cells wider than expected are left as is
+        let expected_width = expected_col_widths.get(i).copied().unwrap_or(0);
+        buffer.push_str(&" ".repeat(expected_width.saturating_sub(cell.width())));
         buffer.push_str(" | ");
     }
     buffer.push('\n');
     trim_trailing_space_before_newline(buffer); // Dirty hack to fix trailing space
-                                                // Follow it with a separator
-                                                // Remember lengths of each column
-                                                // to align them later
 }
 
 fn render_table_heading_separator(
     table: &mdast::Table,
     buffer: &mut String,
-    expected_col_lengths: &[usize],
+    expected_col_widths: &[usize],
 ) {
     buffer.push('|');
     for (i, align) in table.align.iter().enumerate() {
-        let padding = if let Some(len) = expected_col_lengths.get(i) {
-            len - 1
-        } else {
-            // Default padding, to avoid overflow
-            2
-        };
+        let min_width = min_separator_width(align);
+        let width = expected_col_widths
+            .get(i)
+            .copied()
+            .unwrap_or(min_width)
+            .max(min_width);
         match align {
-            AlignKind::Left => buffer.push_str(&format!(" :{} |", &"-".repeat(padding))),
-            AlignKind::Right => buffer.push_str(&format!(" {}: |", &"-".repeat(padding))),
-            AlignKind::Center => buffer.push_str(&format!(" :{}: |", &"-".repeat(padding - 1))),
-            AlignKind::None => buffer.push_str(&format!(" -{}- |", &"-".repeat(padding - 1))),
+            AlignKind::Left => buffer.push_str(&format!(" :{} |", "-".repeat(width - 1))),
+            AlignKind::Right => buffer.push_str(&format!(" {}: |", "-".repeat(width - 1))),
+            AlignKind::Center => buffer.push_str(&format!(" :{}: |", "-".repeat(width - 2))),
+            AlignKind::None => buffer.push_str(&format!(" {} |", "-".repeat(width))),
         }
     }
     buffer.push('\n');
@@ -112,6 +132,27 @@ fn escape_special_characters(str: &str) -> String {
         .replace('<', "\\<")
 }
 
+/// Escape special characters of the text.
+/// Characters that are escaped in the source are written the same way,
+/// e.g. "\\\"" or "&quot;" are not turned into a quote that typography would change
+fn escape_text(text: &str, escapes: &[common::typography::Escape]) -> String {
+    let mut result = String::new();
+    let mut end = 0;
+    for escape in escapes {
+        let is_in_place = text
+            .get(escape.offset..)
+            .is_some_and(|rest| rest.starts_with(escape.char));
+        if escape.offset < end || !is_in_place {
+            continue;
+        }
+        result.push_str(&escape_special_characters(&text[end..escape.offset]));
+        result.push_str(&escape.source);
+        end = escape.offset + escape.char.len_utf8();
+    }
+    result.push_str(&escape_special_characters(&text[end..]));
+    result
+}
+
 /// Check that there's another definition node that follows this one.
 /// For instance:
 /// [org]: https://www.example.org
@@ -120,7 +161,12 @@ fn definition_is_followed_by_other_definition(
     d: &markdown::mdast::Definition,
     source: &str,
 ) -> bool {
-    let line_number = d.position.as_ref().unwrap().end.line;
+    // Definitions without position have been moved by the formatter,
+    // their grouping is handled on the root level
+    let line_number = match &d.position {
+        Some(position) => position.end.line,
+        None => return true,
+    };
     let following_line = source.lines().nth(line_number).unwrap_or_default();
     let ast = common::ast::parse(following_line).unwrap();
     ast.children().is_some_and(|children| {
@@ -131,6 +177,28 @@ fn definition_is_followed_by_other_definition(
     })
 }
 
+/// Builds a code fence according to the style options.
+/// Fence is always longer than any fence-like sequence
+/// inside the code so the block can't be closed prematurely
+fn code_fence(c: &mdast::Code, options: &FormattingOptions) -> String {
+    let fence_char = options.code_block.fence_style.as_char();
+    let longest_inner_fence = c
+        .value
+        .lines()
+        .map(|line| {
+            line.trim_start()
+                .chars()
+                .take_while(|ch| *ch == fence_char)
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+    fence_char.to_string().repeat(std::cmp::max(
+        options.code_block.fence_length,
+        longest_inner_fence + 1,
+    ))
+}
+
 /// Render Markdown file from AST
 fn to_md(
     node: &mdast::Node,
@@ -141,7 +209,7 @@ fn to_md(
 ) {
     match node {
         Node::Root(r) => {
-            for child in &r.children {
+            for (i, child) in r.children.iter().enumerate() {
                 to_md(child, buffer, context, source, options);
                 buffer.push('\n');
                 // Only when HTML is on a Root-level
@@ -149,6 +217,15 @@ fn to_md(
                 if let Node::Html(_) = child {
                     buffer.push('\n');
                 }
+                // Moved definitions are kept together,
+                // separated from the rest by a newline
+                if let Node::Definition(d) = child {
+                    if d.position.is_none()
+                        && !matches!(r.children.get(i + 1), Some(Node::Definition(_)))
+                    {
+                        buffer.push('\n');
+                    }
+                }
             }
         }
         Node::Heading(heading) => {
@@ -169,7 +246,11 @@ fn to_md(
             }
         }
         Node::Text(t) => {
-            let text = escape_special_characters(&t.value);
+            let escapes = t
+                .position
+                .as_ref()
+                .and_then(|p| options.escapes.get(&p.start.offset));
+            let text = escape_text(&t.value, escapes.map_or(&[], Vec::as_slice));
             match context {
                 Context::Blockquote(ctx) => buffer.push_str(
                     &text.replace('\n', &format!("\n{}", "> ".repeat(ctx.depth).as_str())),
@@ -232,6 +313,7 @@ fn to_md(
             if let Context::List(ctx) = context {
                 nesting_level = ctx.nesting_level + 1
             }
+            let spread = options.list.item_spacing.is_spread(l);
             for child in &l.children {
                 if let Context::Blockquote(_) = context {
                     if child != l.children.first().unwrap() {
@@ -253,17 +335,19 @@ fn to_md(
                         nesting_level,
                         is_ordered: l.ordered,
                         num_item: start,
-                        spread: l.spread,
+                        spread,
                     }),
                     source,
                     options,
                 );
-                start += 1;
+                if options.list.ordered_numbering == OrderedListNumbering::Ordered {
+                    start += 1;
+                }
                 // Spread list(also called loose in CommonMark) is when
                 // at least one element is new-line separated. We force
                 // to be consistent and add newlines everywhere except
                 // last element because it will have newline anyways
-                if l.spread && child != l.children.last().unwrap() {
+                if spread && child != l.children.last().unwrap() {
                     buffer.push('\n');
                 }
             }
@@ -272,7 +356,11 @@ fn to_md(
             if let Context::List(ctx) = context {
                 buffer.push_str(&"  ".repeat(ctx.nesting_level));
                 if ctx.is_ordered {
-                    buffer.push_str(&format!("{}.", ctx.num_item));
+                    buffer.push_str(&format!(
+                        "{}{}",
+                        ctx.num_item,
+                        options.list.ordered_delimiter.as_str()
+                    ));
                 } else {
                     buffer.push_str(options.list.sign_style.as_str());
                 }
@@ -328,9 +416,11 @@ fn to_md(
             if let Some(lang) = &c.lang {
                 syntax_highlight = lang;
             }
+            let fence = code_fence(c, options);
             match context {
                 Context::Blockquote(_) => buffer.push_str(
-                    &format!("```{}\n{}\n```\n", syntax_highlight, c.value,).replace('\n', "\n> "),
+                    &format!("{fence}{}\n{}\n{fence}\n", syntax_highlight, c.value,)
+                        .replace('\n', "\n> "),
                 ),
                 Context::List(ctx) => {
                     // Add horizontal padding to align code block with list
@@ -339,37 +429,45 @@ fn to_md(
                     } else {
                         format!("\n{}", "   ".repeat(ctx.nesting_level + 1))
                     };
-                    let mut code_block = format!("\n```{}\n{}\n```\n", syntax_highlight, c.value);
+                    let mut code_block =
+                        format!("\n{fence}{}\n{}\n{fence}\n", syntax_highlight, c.value);
                     code_block = code_block.replace('\n', &padding_left);
                     code_block = remove_trailing_newline_and_space(&code_block);
                     code_block.push('\n');
                     buffer.push_str(&code_block);
                 }
-                _ => buffer.push_str(&format!("```{}\n{}\n```\n", syntax_highlight, c.value)),
+                _ => buffer.push_str(&format!(
+                    "{fence}{}\n{}\n{fence}\n",
+                    syntax_highlight, c.value
+                )),
             }
         }
-        Node::InlineCode(c) => {
-            buffer.push_str(&format!("`{}`", &c.value));
-        }
+        Node::InlineCode(c) => match context {
+            // Pipe would end the cell even inside of a code span
+            Context::TableCell => buffer.push_str(&format!("`{}`", &c.value.replace('|', "\\|"))),
+            _ => buffer.push_str(&format!("`{}`", &c.value)),
+        },
         Node::Emphasis(e) => {
-            buffer.push('*');
+            let marker = match options.emphasis.style {
+                EmphasisStyle::Underscore if is_intraword(e.position.as_ref(), source) => "*",
+                ref style => style.as_str(),
+            };
+            buffer.push_str(marker);
             for child in &e.children {
                 to_md(child, buffer, context, source, options);
             }
-            buffer.push('*');
+            buffer.push_str(marker);
         }
         Node::Strong(s) => {
-            match options.strong.style {
-                StrongStyle::Asterisk => buffer.push_str("**"),
-                StrongStyle::Underscore => buffer.push_str("__"),
-            }
+            let marker = match options.strong.style {
+                StrongStyle::Underscore if !is_intraword(s.position.as_ref(), source) => "__",
+                _ => "**",
+            };
+            buffer.push_str(marker);
             for child in &s.children {
                 to_md(child, buffer, context, source, options);
             }
-            match options.strong.style {
-                StrongStyle::Asterisk => buffer.push_str("**"),
-                StrongStyle::Underscore => buffer.push_str("__"),
-            }
+            buffer.push_str(marker);
         }
         Node::Delete(d) => {
             if is_superscript(d, source) {
@@ -391,9 +489,19 @@ fn to_md(
         }
         Node::Link(l) => {
             if is_auto_or_bare_link(l) {
-                buffer.push('<');
-                buffer.push_str(&l.url);
-                buffer.push('>');
+                match options.link.autolink_style {
+                    AutolinkStyle::Bare if can_be_bare_link(l, source) => {
+                        // Text is used because parser adds "mailto:" to e-mails
+                        if let Some(Node::Text(t)) = l.children.first() {
+                            buffer.push_str(&t.value);
+                        }
+                    }
+                    _ => {
+                        buffer.push('<');
+                        buffer.push_str(&l.url);
+                        buffer.push('>');
+                    }
+                }
             } else {
                 buffer.push('[');
                 for child in &l.children {
@@ -494,6 +602,7 @@ fn to_md(
                         )),
                         Context::List(_) => {}
                         Context::BlockquoteInList(_) => {}
+                        Context::TableCell => {}
                     }
                 }
             }
@@ -503,16 +612,22 @@ fn to_md(
             }
         }
         Node::ThematicBreak(_) => {
-            buffer.push_str("---\n");
+            buffer.push_str(&options.thematic_break.value);
+            buffer.push('\n');
         }
         Node::Html(h) => {
             buffer.push_str(&h.value);
         }
         Node::ImageReference(ir) => {
-            buffer.push_str(&format!("![{}][{}]", ir.alt, ir.identifier));
+            let label = match &ir.label {
+                Some(label) => references::escape_label(label),
+                None => ir.identifier.clone(),
+            };
+            buffer.push_str(&format!("![{}][{}]", ir.alt, label));
         }
         Node::Definition(d) => {
             if let Some(label) = &d.label {
+                let label = references::escape_label(label);
                 buffer.push_str(&format!("[{}]: {}", label, d.url));
             } else {
                 buffer.push_str(&format!("[{}]: {}", d.identifier, d.url));
@@ -547,7 +662,7 @@ fn to_md(
             }
             buffer.push(']');
             if let Some(label) = &lr.label {
-                buffer.push_str(&format!("[{}]", &label));
+                buffer.push_str(&format!("[{}]", references::escape_label(label)));
             } else {
                 buffer.push_str(&format!("[{}]", &lr.identifier));
             }
@@ -574,18 +689,19 @@ fn to_md(
             }
         }
         Node::Table(t) => {
-            let headers_cols_lengths = calculate_max_col_len(t, source);
-            for child in &t.children {
-                render_table_row(
-                    child,
-                    buffer,
-                    context,
-                    source,
-                    options,
-                    &headers_cols_lengths,
-                );
-                if child == t.children.first().unwrap() {
-                    render_table_heading_separator(t, buffer, &headers_cols_lengths);
+            let rows = render_table_cells(t, source, options);
+            let (cells_widths, separator_widths) = match options.table.style {
+                TableStyle::Aligned => {
+                    let widths = calculate_max_col_width(t, &rows, options.table.max_column_width);
+                    (widths.clone(), widths)
+                }
+                // Cells are not padded, separator is rendered with a minimal width
+                TableStyle::Compact => (vec![0; t.align.len()], vec![3; t.align.len()]),
+            };
+            for (i, cells) in rows.iter().enumerate() {
+                render_table_row(buffer, cells, &cells_widths);
+                if i == 0 {
+                    render_table_heading_separator(t, buffer, &separator_widths);
                 }
             }
         }
@@ -611,20 +727,60 @@ fn to_md(
     }
 }
 
+/// Byte order mark that some editors(mostly on Windows) put at the beginning of UTF-8 files
+const UTF8_BOM: &str = "\u{FEFF}";
+
+/// Line ending that formatted file shall use
+fn get_line_ending(file: &common::MarkDownFile, config: &common::Config) -> &'static str {
+    match config.fmt.line_endings {
+        common::LineEndings::Auto => common::detect_line_ending(&file.content),
+        common::LineEndings::Lf => "\n",
+        common::LineEndings::Crlf => "\r\n",
+    }
+}
+
+/// Split file content into BOM(empty when there is none) and the rest of the content
+fn split_bom(file: &common::MarkDownFile) -> (&'static str, &str) {
+    match file.content.strip_prefix(UTF8_BOM) {
+        Some(content) => (UTF8_BOM, content),
+        None => ("", file.content.as_str()),
+    }
+}
+
 /// Return formatted Markdown file
 pub fn fmt_markdown(file: &common::MarkDownFile, config: &common::Config) -> common::MarkDownFile {
     log::debug!("Format {:#?} with config: {:#?}", &file.path, &config);
 
-    let fmt_options = FormattingOptions::from(config, file);
+    // Formatting is done on a BOM-less document with LF line endings.
+    // BOM and requested line endings are restored afterwards
+    let line_ending = get_line_ending(file, config);
+    let (bom, content) = split_bom(file);
+    let normalized = common::MarkDownFile {
+        path: file.path.clone(),
+        content: common::toc::update(
+            &common::heading_numbering::update(
+                &content.replace("\r\n", "\n"),
+                &config.style.heading_numbering,
+            ),
+            &config.style.toc,
+        ),
+        issues: vec![],
+    };
+
+    let mut fmt_options = FormattingOptions::from(config, &normalized);
     log::debug!("Formatting options: {:#?}", &fmt_options);
 
     let mut buffer: String = String::from("");
-    let ast = common::ast::parse(&file.content).unwrap();
+    let mut ast = common::ast::parse(&normalized.content).unwrap();
+    let issues = code_blocks::fmt_code_blocks(&mut ast, file, config, |_| true);
+    references::fmt_references(&mut ast, &config.fmt.references);
+    fmt_options.escapes =
+        common::typography::normalize(&mut ast, &normalized.content, &config.fmt.typography);
     to_md(
         &ast,
         &mut buffer,
         &Context::Document,
-        &file.content,
+        &normalized.content,
         &fmt_options,
     );
 
@@ -632,19 +788,222 @@ pub fn fmt_markdown(file: &common::MarkDownFile, config: &common::Config) -> com
     buffer = remove_trailing_newline_and_space(&buffer);
     buffer.push('\n');
 
+    if line_ending != "\n" {
+        log::debug!("Converting line endings to {:#?}", &line_ending);
+        buffer = buffer.replace('\n', line_ending);
+    }
+    buffer.insert_str(0, bom);
+
     common::MarkDownFile {
         path: file.path.clone(),
         content: buffer,
+        issues,
+    }
+}
+
+/// Return Markdown file where only top-level blocks(paragraphs, lists, tables, etc.)
+/// that intersect with given range of lines(starts at 1) are formatted.
+/// Heading numbers and table of contents are updated only within these blocks,
+/// code blocks outside of them are neither formatted nor reported.
+/// Links within these blocks are converted according to the references config,
+/// while definitions outside of them are not moved, sorted or removed.
+/// Everything outside of these blocks is left byte-identical
+pub fn fmt_markdown_range(
+    file: &common::MarkDownFile,
+    config: &common::Config,
+    lines: &std::ops::RangeInclusive<usize>,
+) -> common::MarkDownFile {
+    log::debug!("Format lines {:#?} of {:#?}", &lines, &file.path);
+
+    let line_ending = get_line_ending(file, config);
+    let (bom, content) = split_bom(file);
+    let normalized = common::MarkDownFile {
+        path: file.path.clone(),
+        content: content.replace("\r\n", "\n"),
         issues: vec![],
+    };
+
+    // Style is detected from the whole document
+    let mut fmt_options = FormattingOptions::from(config, &normalized);
+    log::debug!("Formatting options: {:#?}", &fmt_options);
+
+    // Parse original content, so offsets of blocks point to unchanged bytes
+    let (content, spans) = update_numbering_and_toc_in_range(content, config, lines, line_ending);
+    let is_in_range = |position: Option<&markdown::unist::Position>| {
+        position.is_some_and(|p| spans.iter().any(|span| span.contains(&p.start.offset)))
+    };
+    let mut ast = common::ast::parse(&content).unwrap();
+    let definitions_before = collect_definition_ids(&ast);
+    let issues = code_blocks::fmt_code_blocks(&mut ast, file, config, |code| {
+        is_in_range(code.position.as_ref())
+    });
+    // Definitions outside of the range must stay where they are
+    let references = common::ReferencesConfig {
+        definitions: common::DefinitionsPlacement::Preserve,
+        sort: false,
+        remove_unused: false,
+        ..config.fmt.references.clone()
+    };
+    references::fmt_references(&mut ast, &references);
+    fmt_options.escapes = common::typography::normalize(&mut ast, &content, &config.fmt.typography);
+    let blocks = ast
+        .children()
+        .unwrap()
+        .iter()
+        .filter(|block| is_in_range(block.position()))
+        .collect::<Vec<&Node>>();
+
+    // Definitions of links that were converted to references within the range
+    let referenced = blocks
+        .iter()
+        .flat_map(|block| common::ast::BfsIterator::from(block))
+        .filter_map(|n| match n {
+            Node::LinkReference(r) => Some(r.identifier.clone()),
+            _ => None,
+        })
+        .collect::<std::collections::HashSet<String>>();
+    let new_definitions = ast
+        .children()
+        .unwrap()
+        .iter()
+        .filter(|n| match n {
+            Node::Definition(d) => {
+                referenced.contains(&d.identifier) && !definitions_before.contains(&d.identifier)
+            }
+            _ => false,
+        })
+        .map(|n| {
+            let mut buffer = String::new();
+            to_md(n, &mut buffer, &Context::Document, &content, &fmt_options);
+            buffer.trim_end().to_string()
+        })
+        .collect::<Vec<String>>();
+
+    let mut result = content.to_string();
+    // Inserted after all blocks to replace, so their offsets remain valid
+    if !new_definitions.is_empty() {
+        let end = match config.fmt.references.definitions {
+            common::DefinitionsPlacement::Document => result.len(),
+            _ => blocks.last().unwrap().position().unwrap().end.offset,
+        };
+        let end = result[..end].trim_end_matches(['\r', '\n']).len();
+        let definitions = new_definitions.join(line_ending);
+        result.insert_str(end, &format!("{line_ending}{line_ending}{definitions}"));
+    }
+    // Go from the end, so offsets of blocks that are not yet replaced remain valid
+    for block in blocks.iter().rev() {
+        let position = block.position().unwrap();
+        let mut buffer: String = String::from("");
+        to_md(
+            block,
+            &mut buffer,
+            &Context::Document,
+            &content,
+            &fmt_options,
+        );
+        buffer = remove_trailing_newline_and_space(&buffer).replace("\r\n", "\n");
+        if line_ending != "\n" {
+            buffer = buffer.replace('\n', line_ending);
+        }
+        // Position of some blocks(e.g. list followed by another list)
+        // includes blank lines after them, they must stay untouched
+        let original = &content[position.start.offset..position.end.offset];
+        buffer.push_str(&original[original.trim_end_matches(['\r', '\n']).len()..]);
+        result.replace_range(position.start.offset..position.end.offset, &buffer);
+    }
+    result.insert_str(0, bom);
+
+    common::MarkDownFile {
+        path: file.path.clone(),
+        content: result,
+        issues,
+    }
+}
+
+/// Identifiers of all definitions of the document
+fn collect_definition_ids(ast: &Node) -> std::collections::HashSet<String> {
+    common::ast::BfsIterator::from(ast)
+        .filter_map(|n| match n {
+            Node::Definition(d) => Some(d.identifier.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Update numbers of headings and table of contents that intersect with given lines.
+/// Returns updated content and offsets of top-level blocks that intersect with lines
+/// in it. Table of contents is regenerated as a whole, so all its blocks are included
+fn update_numbering_and_toc_in_range(
+    content: &str,
+    config: &common::Config,
+    lines: &std::ops::RangeInclusive<usize>,
+    line_ending: &str,
+) -> (String, Vec<std::ops::Range<usize>>) {
+    let intersects = |p: &markdown::unist::Position| {
+        p.start.line <= *lines.end() && p.end.line >= *lines.start()
+    };
+    let ast = common::ast::parse(content).unwrap();
+    let mut spans = ast
+        .children()
+        .unwrap()
+        .iter()
+        .filter_map(|block| block.position())
+        .filter(|p| intersects(p))
+        .map(|p| p.start.offset..p.end.offset)
+        .collect::<Vec<std::ops::Range<usize>>>();
+    let mut edits = common::heading_numbering::check(&ast, &config.style.heading_numbering)
+        .into_iter()
+        .filter(|heading| intersects(&heading.position))
+        .map(|heading| {
+            let number = match &heading.expected {
+                Some(number) => format!("{number} "),
+                None => String::new(),
+            };
+            (heading.prefix, number)
+        })
+        .collect::<Vec<(std::ops::Range<usize>, String)>>();
+    if let Some(location) = common::toc::locate(&ast).filter(|l| intersects(&l.position)) {
+        // Table of contents lists headings with their updated numbers
+        let numbered = common::heading_numbering::update(
+            &content.replace("\r\n", "\n"),
+            &config.style.heading_numbering,
+        );
+        let toc = common::toc::render(
+            &common::toc::collect(&common::ast::parse(&numbered).unwrap(), &config.style.toc),
+            &config.style.toc,
+        );
+        let toc = match toc.is_empty() {
+            true => String::from("\n\n"),
+            false => format!("\n\n{toc}\n\n"),
+        };
+        edits.push((location.content, toc.replace('\n', line_ending)));
+        spans.push(location.position.start.offset..location.position.end.offset);
+    }
+
+    let mut result = content.to_string();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, replacement) in edits {
+        let delta = replacement.len() as isize - range.len() as isize;
+        result.replace_range(range.clone(), &replacement);
+        for span in spans.iter_mut() {
+            if span.start >= range.end {
+                span.start = span.start.saturating_add_signed(delta);
+                span.end = span.end.saturating_add_signed(delta);
+            } else if span.start <= range.start && range.end <= span.end {
+                span.end = span.end.saturating_add_signed(delta);
+            }
+        }
     }
+    (result, spans)
 }
 
 pub fn check_md_format(
     file: &common::MarkDownFile,
     config: &common::Config,
 ) -> Vec<common::CheckIssue> {
+    let mut formatted = fmt_markdown(file, config);
     let mut issues: Vec<common::CheckIssue> = vec![];
-    let formatted = &fmt_markdown(file, config);
+    issues.append(&mut formatted.issues);
     if !file.content.eq(&formatted.content) {
         let mut issue = common::CheckIssueBuilder::default()
             .set_category(common::IssueCategory::Formatting)
@@ -0,0 +1,374 @@
+use common::{DefinitionsPlacement, LinkReferenceStyle, ReferencesConfig};
+use markdown::mdast::{Definition, Link, LinkReference, Node, ReferenceKind};
+
+/// Reorganizes links and their definitions according to the config:
+///   - converts inline links to reference style and back
+///   - normalizes labels(lowercase, single spaces)
+///   - drops unused definitions
+///   - moves definitions to the end of the document or of each section
+///   - sorts definitions by label
+///
+/// Root-level definitions touched by this function lose their position,
+/// so renderer places them together instead of looking into the source
+pub fn fmt_references(ast: &mut Node, config: &ReferencesConfig) {
+    if !is_enabled(config) {
+        return;
+    }
+
+    // Identifiers that were referenced before conversion of links
+    let referenced_before = collect_references(ast);
+
+    let mut new_definitions: Vec<Definition> = vec![];
+    match config.links {
+        LinkReferenceStyle::Preserve => {}
+        LinkReferenceStyle::Reference => {
+            let mut definitions = collect_definitions(ast);
+            walk_mut(ast, &mut |node| {
+                if let Node::Link(link) = node {
+                    if let Some(reference) = to_link_reference(link, &mut definitions) {
+                        *node = Node::LinkReference(reference);
+                    }
+                }
+            });
+            new_definitions = definitions
+                .into_iter()
+                .filter(|d| d.position.is_none())
+                .collect();
+        }
+        LinkReferenceStyle::Inline => {
+            let definitions = collect_definitions(ast);
+            walk_mut(ast, &mut |node| {
+                if let Node::LinkReference(reference) = node {
+                    if let Some(link) = to_link(reference, &definitions) {
+                        *node = Node::Link(link);
+                    }
+                }
+            });
+        }
+    }
+
+    // Drop unused definitions. Definitions which references were all
+    // converted to inline links are dropped as well
+    let referenced_after = collect_references(ast);
+    let is_used = |d: &Definition| {
+        referenced_after.contains(&d.identifier)
+            || (!config.remove_unused && !referenced_before.contains(&d.identifier))
+    };
+    retain_definitions(ast, &is_used);
+    new_definitions.retain(is_used);
+
+    if config.normalize_labels {
+        walk_mut(ast, &mut normalize_label);
+        for definition in new_definitions.iter_mut() {
+            definition.label = Some(normalize_label_text(&label_of(definition)));
+        }
+    }
+
+    if let Node::Root(root) = ast {
+        match config.definitions {
+            DefinitionsPlacement::Preserve => {
+                root.children
+                    .extend(new_definitions.into_iter().map(Node::Definition));
+                if config.sort {
+                    sort_consecutive_definitions(&mut root.children);
+                }
+            }
+            DefinitionsPlacement::Document => {
+                let mut definitions = take_definitions(&mut root.children);
+                definitions.extend(new_definitions);
+                if config.sort {
+                    sort_definitions(&mut definitions);
+                }
+                root.children
+                    .extend(definitions.into_iter().map(Node::Definition));
+            }
+            DefinitionsPlacement::Section => {
+                let mut definitions = take_definitions(&mut root.children);
+                definitions.extend(new_definitions);
+                place_definitions_per_section(&mut root.children, definitions, config.sort);
+            }
+        }
+        for child in root.children.iter_mut() {
+            if let Node::Definition(definition) = child {
+                definition.position = None;
+            }
+        }
+    }
+}
+
+fn is_enabled(config: &ReferencesConfig) -> bool {
+    config.links != LinkReferenceStyle::Preserve
+        || config.definitions != DefinitionsPlacement::Preserve
+        || config.sort
+        || config.normalize_labels
+        || config.remove_unused
+}
+
+/// Calls f for every node of the AST, parents first
+fn walk_mut(node: &mut Node, f: &mut impl FnMut(&mut Node)) {
+    f(node);
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            walk_mut(child, f);
+        }
+    }
+}
+
+fn collect_definitions(ast: &Node) -> Vec<Definition> {
+    common::ast::BfsIterator::from(ast)
+        .filter_map(|n| match n {
+            Node::Definition(d) => Some(d.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Identifiers of all link and image references
+fn collect_references(ast: &Node) -> std::collections::HashSet<String> {
+    common::ast::BfsIterator::from(ast)
+        .filter_map(|n| match n {
+            Node::LinkReference(r) => Some(r.identifier.clone()),
+            Node::ImageReference(r) => Some(r.identifier.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Remove definitions that do not satisfy predicate from the whole AST
+fn retain_definitions(node: &mut Node, predicate: &impl Fn(&Definition) -> bool) {
+    if let Some(children) = node.children_mut() {
+        children.retain(|child| match child {
+            Node::Definition(d) => predicate(d),
+            _ => true,
+        });
+        for child in children {
+            retain_definitions(child, predicate);
+        }
+    }
+}
+
+/// Remove all definitions from the AST and return them in the order of appearance
+fn take_definitions(children: &mut Vec<Node>) -> Vec<Definition> {
+    let mut definitions: Vec<Definition> = vec![];
+    let mut i = 0;
+    while i < children.len() {
+        if let Node::Definition(d) = &children[i] {
+            definitions.push(d.clone());
+            children.remove(i);
+        } else {
+            if let Some(grand_children) = children[i].children_mut() {
+                definitions.append(&mut take_definitions(grand_children));
+            }
+            i += 1;
+        }
+    }
+    definitions
+}
+
+/// Plain text of the inline content
+fn to_text(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|n| match n {
+            Node::Text(t) => t.value.clone(),
+            Node::InlineCode(c) => c.value.clone(),
+            _ => n.children().map(|c| to_text(c)).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Link label can't be longer than 999 characters
+const MAX_LABEL_LENGTH: usize = 999;
+
+/// Cut the text to fit into the max label length once escaped
+fn to_label(text: &str, max_length: usize) -> String {
+    let mut label = String::new();
+    let mut length = 0;
+    for c in text.chars() {
+        length += match c {
+            '[' | ']' | '\\' => 2,
+            _ => 1,
+        };
+        if length > max_length {
+            break;
+        }
+        label.push(c);
+    }
+    label.trim_end().to_string()
+}
+
+/// Escape characters that are not allowed in labels of links and definitions.
+/// Parsed labels have escapes resolved, so they have to be escaped back when printed
+pub fn escape_label(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn label_of(definition: &Definition) -> String {
+    definition
+        .label
+        .clone()
+        .unwrap_or(definition.identifier.clone())
+}
+
+/// Lowercase label and collapse whitespace into a single space
+fn normalize_label_text(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn normalize_label(node: &mut Node) {
+    match node {
+        Node::Definition(d) => d.label = Some(normalize_label_text(&label_of(d))),
+        Node::LinkReference(r) => {
+            r.label = Some(normalize_label_text(
+                r.label.as_ref().unwrap_or(&r.identifier),
+            ));
+            // Label can't be inferred from the content anymore
+            r.reference_kind = ReferenceKind::Full;
+        }
+        Node::ImageReference(r) => {
+            r.label = Some(normalize_label_text(
+                r.label.as_ref().unwrap_or(&r.identifier),
+            ));
+            r.reference_kind = ReferenceKind::Full;
+        }
+        _ => {}
+    }
+}
+
+/// Convert inline link into a reference. Definition with the same URL and title
+/// is re-used, otherwise a new one is added with a label made from link's text.
+/// Auto-links and links to document's fragments are kept inline
+fn to_link_reference(link: &Link, definitions: &mut Vec<Definition>) -> Option<LinkReference> {
+    if crate::style::is_auto_or_bare_link(link) || link.url.starts_with('#') {
+        return None;
+    }
+    let definition = match definitions
+        .iter()
+        .find(|d| d.url.eq(&link.url) && d.title.eq(&link.title))
+    {
+        Some(definition) => definition.clone(),
+        None => {
+            let mut text = normalize_label_text(&to_text(&link.children));
+            if text.is_empty() {
+                text = link.url.clone();
+            }
+            let is_taken = |label: &str| {
+                definitions
+                    .iter()
+                    .any(|d| normalize_label_text(&label_of(d)).eq(label) || d.identifier.eq(label))
+            };
+            let mut unique_label = to_label(&text, MAX_LABEL_LENGTH);
+            let mut n = 2;
+            while is_taken(&unique_label) {
+                let suffix = format!(" {n}");
+                unique_label = to_label(&text, MAX_LABEL_LENGTH - suffix.len()) + &suffix;
+                n += 1;
+            }
+            let definition = Definition {
+                position: None,
+                url: link.url.clone(),
+                title: link.title.clone(),
+                identifier: escape_label(&unique_label),
+                label: Some(unique_label),
+            };
+            definitions.push(definition.clone());
+            definition
+        }
+    };
+    Some(LinkReference {
+        children: link.children.clone(),
+        position: link.position.clone(),
+        reference_kind: ReferenceKind::Full,
+        identifier: definition.identifier.clone(),
+        label: Some(label_of(&definition)),
+    })
+}
+
+/// Convert reference into an inline link when its definition exists
+fn to_link(reference: &LinkReference, definitions: &[Definition]) -> Option<Link> {
+    definitions
+        .iter()
+        .find(|d| d.identifier.eq(&reference.identifier))
+        .map(|d| Link {
+            children: reference.children.clone(),
+            position: reference.position.clone(),
+            url: d.url.clone(),
+            title: d.title.clone(),
+        })
+}
+
+fn sort_definitions(definitions: &mut [Definition]) {
+    definitions.sort_by_key(|d| normalize_label_text(&label_of(d)));
+}
+
+/// Sort every group of definitions that follow each other
+fn sort_consecutive_definitions(children: &mut [Node]) {
+    let mut start = 0;
+    while start < children.len() {
+        let mut end = start;
+        while end < children.len() && matches!(children[end], Node::Definition(_)) {
+            end += 1;
+        }
+        children[start..end].sort_by_key(|n| match n {
+            Node::Definition(d) => normalize_label_text(&label_of(d)),
+            _ => String::new(),
+        });
+        start = end + 1;
+    }
+}
+
+/// Put every definition at the end of the section(content till the next heading)
+/// where it is referenced first. Unreferenced definitions go to the end of the document
+fn place_definitions_per_section(
+    children: &mut Vec<Node>,
+    definitions: Vec<Definition>,
+    sort: bool,
+) {
+    // Index of the first child of every section
+    let mut section_starts: Vec<usize> = vec![0];
+    section_starts.extend(
+        children
+            .iter()
+            .enumerate()
+            .filter(|(i, n)| *i > 0 && matches!(n, Node::Heading(_)))
+            .map(|(i, _)| i),
+    );
+    let section_of = |child_index: usize| {
+        section_starts
+            .iter()
+            .rposition(|start| *start <= child_index)
+            .unwrap_or(0)
+    };
+
+    let mut sections: Vec<Vec<Definition>> = vec![vec![]; section_starts.len() + 1];
+    for definition in definitions {
+        let first_use = children
+            .iter()
+            .position(|child| collect_references(child).contains(&definition.identifier));
+        match first_use {
+            Some(child_index) => sections[section_of(child_index)].push(definition),
+            None => sections[section_starts.len()].push(definition),
+        }
+    }
+    let unused = sections.pop().unwrap_or_default();
+    sections.last_mut().unwrap().extend(unused);
+
+    // Insert from the end, so indexes of sections before remain valid
+    for (section, mut definitions) in sections.into_iter().enumerate().rev() {
+        if sort {
+            sort_definitions(&mut definitions);
+        }
+        let end = section_starts
+            .get(section + 1)
+            .copied()
+            .unwrap_or(children.len());
+        children.splice(end..end, definitions.into_iter().map(Node::Definition));
+    }
+}
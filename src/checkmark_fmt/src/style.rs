@@ -39,6 +39,109 @@ pub enum StrongStyle {
     Asterisk,
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum EmphasisStyle {
+    /// Wrapped with "*"
+    #[default]
+    Asterisk,
+    /// Wrapped with "_"
+    Underscore,
+}
+
+impl EmphasisStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Asterisk => "*",
+            Self::Underscore => "_",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CodeFenceStyle {
+    /// Fenced with "```"
+    #[default]
+    Backtick,
+    /// Fenced with "~~~"
+    Tilde,
+}
+
+impl CodeFenceStyle {
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Backtick => '`',
+            Self::Tilde => '~',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OrderedListNumbering {
+    /// Every item repeats the number of the first item, e.g. "1." everywhere
+    One,
+    /// Items are numbered incrementally starting from the first item's number
+    #[default]
+    Ordered,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OrderedListDelimiter {
+    /// "1."
+    #[default]
+    Period,
+    /// "1)"
+    Parenthesis,
+}
+
+impl OrderedListDelimiter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Period => ".",
+            Self::Parenthesis => ")",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ListItemSpacing {
+    /// Keep each list tight or loose(spread) as it was written
+    #[default]
+    Preserve,
+    /// No blank lines between list items
+    Tight,
+    /// Blank line between every list item
+    Loose,
+}
+
+impl ListItemSpacing {
+    /// Whether given list shall be rendered as a spread(loose) one
+    pub fn is_spread(&self, list: &markdown::mdast::List) -> bool {
+        match self {
+            Self::Preserve => list.spread,
+            Self::Tight => false,
+            Self::Loose => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TableStyle {
+    /// Columns are padded so all rows have the same width
+    #[default]
+    Aligned,
+    /// Cells are not padded
+    Compact,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AutolinkStyle {
+    /// "<http://example.com>"
+    #[default]
+    Angle,
+    /// "http://example.com"
+    Bare,
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 #[allow(dead_code)]
 pub struct HeaderOptions {
@@ -50,6 +153,9 @@ pub struct HeaderOptions {
 pub struct ListOptions {
     pub sign_style: ListSignStyle,
     pub num_spaces_after_list_marker: u8,
+    pub ordered_numbering: OrderedListNumbering,
+    pub ordered_delimiter: OrderedListDelimiter,
+    pub item_spacing: ListItemSpacing,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -58,10 +164,34 @@ pub struct StrongOptions {
     pub style: StrongStyle,
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmphasisOptions {
+    pub style: EmphasisStyle,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThematicBreakOptions {
+    /// Exact string used to render a thematic break, e.g. "---"
+    pub value: String,
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 #[allow(dead_code)]
 pub struct CodeBlockOptions {
     pub default_language: String,
+    pub fence_style: CodeFenceStyle,
+    pub fence_length: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableOptions {
+    pub style: TableStyle,
+    pub max_column_width: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkOptions {
+    pub autolink_style: AutolinkStyle,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -69,11 +199,18 @@ pub struct FormattingOptions {
     pub header: HeaderOptions,
     pub list: ListOptions,
     pub strong: StrongOptions,
+    pub emphasis: EmphasisOptions,
+    pub thematic_break: ThematicBreakOptions,
     pub code_block: CodeBlockOptions,
+    pub table: TableOptions,
+    pub link: LinkOptions,
+    /// Characters escaped in the source, filled in by typographic normalization
+    pub escapes: common::typography::Escapes,
 }
 
 impl FormattingOptions {
     pub fn from(config: &common::Config, source: &common::MarkDownFile) -> Self {
+        let ast = common::ast::parse(&source.content).unwrap();
         Self {
             list: ListOptions {
                 sign_style: match config.style.unordered_lists {
@@ -142,6 +279,28 @@ impl FormattingOptions {
                     .style
                     .num_spaces_after_list_marker
                     .unwrap_or(1),
+                ordered_numbering: match config.style.ordered_lists {
+                    common::OrderedListStyle::Consistent => {
+                        detect_ordered_list_numbering(&ast, &source.content)
+                    }
+                    common::OrderedListStyle::One => OrderedListNumbering::One,
+                    common::OrderedListStyle::Ordered => OrderedListNumbering::Ordered,
+                },
+                ordered_delimiter: match config.style.ordered_list_delimiter {
+                    common::OrderedListDelimiterStyle::Consistent => {
+                        detect_ordered_list_delimiter(&ast, &source.content)
+                    }
+                    common::OrderedListDelimiterStyle::Period => OrderedListDelimiter::Period,
+                    common::OrderedListDelimiterStyle::Parenthesis => {
+                        OrderedListDelimiter::Parenthesis
+                    }
+                },
+                item_spacing: match config.style.list_item_spacing {
+                    common::ListItemSpacingStyle::Preserve => ListItemSpacing::Preserve,
+                    common::ListItemSpacingStyle::Consistent => detect_list_item_spacing(&ast),
+                    common::ListItemSpacingStyle::Tight => ListItemSpacing::Tight,
+                    common::ListItemSpacingStyle::Loose => ListItemSpacing::Loose,
+                },
             },
             header: HeaderOptions {
                 style: match config.style.headings {
@@ -205,17 +364,241 @@ impl FormattingOptions {
                     common::BoldStyle::Underscore => StrongStyle::Underscore,
                 },
             },
+            emphasis: EmphasisOptions {
+                style: match config.style.emphasis {
+                    common::EmphasisStyle::Consistent => {
+                        detect_emphasis_style(&ast, &source.content)
+                    }
+                    common::EmphasisStyle::Asterisk => EmphasisStyle::Asterisk,
+                    common::EmphasisStyle::Underscore => EmphasisStyle::Underscore,
+                },
+            },
+            thematic_break: ThematicBreakOptions {
+                value: match config.style.thematic_break {
+                    common::ThematicBreakStyle::Consistent => {
+                        detect_thematic_break(&ast, &source.content)
+                    }
+                    common::ThematicBreakStyle::Dash => String::from("---"),
+                    common::ThematicBreakStyle::Asterisk => String::from("***"),
+                    common::ThematicBreakStyle::Underscore => String::from("___"),
+                },
+            },
             code_block: CodeBlockOptions {
                 default_language: config
                     .style
                     .default_code_block_language
                     .clone()
                     .unwrap_or(String::from("text")),
+                fence_style: match config.style.code_fence {
+                    common::CodeFenceStyle::Consistent => {
+                        detect_code_fence(&ast, &source.content).0
+                    }
+                    common::CodeFenceStyle::Backtick => CodeFenceStyle::Backtick,
+                    common::CodeFenceStyle::Tilde => CodeFenceStyle::Tilde,
+                },
+                fence_length: match config.style.code_fence_length {
+                    // Fence shorter than 3 characters is not a fence
+                    Some(length) => std::cmp::max(length as usize, 3),
+                    None => detect_code_fence(&ast, &source.content).1,
+                },
             },
+            table: TableOptions {
+                style: match config.style.tables {
+                    common::TableStyle::Consistent => detect_table_style(&ast, &source.content),
+                    common::TableStyle::Aligned => TableStyle::Aligned,
+                    common::TableStyle::Compact => TableStyle::Compact,
+                },
+                max_column_width: config.style.max_table_column_width.map(usize::from),
+            },
+            link: LinkOptions {
+                autolink_style: match config.style.autolinks {
+                    common::AutolinkStyle::Consistent => {
+                        detect_autolink_style(&ast, &source.content)
+                    }
+                    common::AutolinkStyle::Angle => AutolinkStyle::Angle,
+                    common::AutolinkStyle::Bare => AutolinkStyle::Bare,
+                },
+            },
+            escapes: common::typography::Escapes::new(),
         }
     }
 }
 
+/// Returns the part of the source that corresponds to the given position
+fn slice_source<'a>(position: Option<&markdown::unist::Position>, source: &'a str) -> &'a str {
+    match position {
+        Some(p) => source.get(p.start.offset..p.end.offset).unwrap_or(""),
+        None => "",
+    }
+}
+
+/// Parses the number of the ordered list item, e.g. "3" from "3. Item"
+fn parse_list_item_number(item: &markdown::mdast::Node, source: &str) -> Option<u32> {
+    slice_source(item.position(), source)
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse::<u32>()
+        .ok()
+}
+
+/// Detect ordered list numbering from the first ordered list with 2+ items.
+/// When the second item repeats the number of the first one - all items share it.
+fn detect_ordered_list_numbering(
+    ast: &markdown::mdast::Node,
+    source: &str,
+) -> OrderedListNumbering {
+    let ordered_list = common::ast::BfsIterator::from(ast)
+        .filter_map(|n| common::ast::try_cast_to_list(n))
+        .find(|l| l.ordered && l.children.len() > 1);
+    if let Some(list) = ordered_list {
+        log::debug!("First ordered list with multiple items: {:#?}", &list);
+        let first = parse_list_item_number(&list.children[0], source);
+        let second = parse_list_item_number(&list.children[1], source);
+        if first.is_some() && first == second {
+            log::debug!("Ordered list items share the same number");
+            return OrderedListNumbering::One;
+        }
+    }
+    log::debug!("Defaulting to the incremental ordered list numbering");
+    OrderedListNumbering::Ordered
+}
+
+/// Detect ordered list delimiter("." or ")") from the first ordered list item
+fn detect_ordered_list_delimiter(
+    ast: &markdown::mdast::Node,
+    source: &str,
+) -> OrderedListDelimiter {
+    let first_ordered_list_item = common::ast::BfsIterator::from(ast)
+        .filter_map(|n| common::ast::try_cast_to_list(n))
+        .filter(|l| l.ordered)
+        .find_map(|l| l.children.first());
+    if let Some(item) = first_ordered_list_item {
+        let delimiter = slice_source(item.position(), source)
+            .trim_start()
+            .chars()
+            .find(|c| !c.is_ascii_digit());
+        if delimiter == Some(')') {
+            log::debug!("First ordered list item is delimited with parenthesis");
+            return OrderedListDelimiter::Parenthesis;
+        }
+    }
+    log::debug!("Defaulting to the period ordered list delimiter");
+    OrderedListDelimiter::Period
+}
+
+/// Detect whether lists are tight or loose from the first list in a file
+fn detect_list_item_spacing(ast: &markdown::mdast::Node) -> ListItemSpacing {
+    match common::ast::BfsIterator::from(ast).find_map(|n| common::ast::try_cast_to_list(n)) {
+        Some(list) if list.spread => ListItemSpacing::Loose,
+        Some(_) => ListItemSpacing::Tight,
+        None => {
+            log::debug!("File has no lists, preserving list item spacing");
+            ListItemSpacing::Preserve
+        }
+    }
+}
+
+/// Detect emphasis style from the first emphasis element in a file
+fn detect_emphasis_style(ast: &markdown::mdast::Node, source: &str) -> EmphasisStyle {
+    let first_emphasis =
+        common::ast::BfsIterator::from(ast).find_map(|n| common::ast::try_cast_to_emphasis(n));
+    match first_emphasis {
+        Some(e) if slice_source(e.position.as_ref(), source).starts_with('_') => {
+            log::debug!("First emphasis el is underscored");
+            EmphasisStyle::Underscore
+        }
+        _ => {
+            log::debug!("Defaulting to the asterisk emphasis");
+            EmphasisStyle::Asterisk
+        }
+    }
+}
+
+/// Detect thematic break from the first thematic break in a file.
+/// Exact string is preserved, so "* * *" stays "* * *"
+fn detect_thematic_break(ast: &markdown::mdast::Node, source: &str) -> String {
+    let first_thematic_break = common::ast::BfsIterator::from(ast).find_map(|n| match n {
+        markdown::mdast::Node::ThematicBreak(t) => Some(t),
+        _ => None,
+    });
+    match first_thematic_break {
+        Some(t) => {
+            let value = slice_source(t.position.as_ref(), source).trim();
+            if value.is_empty() {
+                String::from("---")
+            } else {
+                value.to_string()
+            }
+        }
+        None => {
+            log::debug!("There are no thematic breaks in a file, defaulting to dashes");
+            String::from("---")
+        }
+    }
+}
+
+/// Detect code fence character and length from the first fenced code block in a file
+fn detect_code_fence(ast: &markdown::mdast::Node, source: &str) -> (CodeFenceStyle, usize) {
+    let first_fence = common::ast::BfsIterator::from(ast)
+        .filter_map(|n| common::ast::try_cast_to_code(n))
+        .map(|c| slice_source(c.position.as_ref(), source).trim_start())
+        .find(|c| c.starts_with("```") || c.starts_with("~~~"));
+    match first_fence {
+        Some(fence) => {
+            let style = if fence.starts_with('~') {
+                CodeFenceStyle::Tilde
+            } else {
+                CodeFenceStyle::Backtick
+            };
+            let length = fence.chars().take_while(|c| *c == style.as_char()).count();
+            (style, length)
+        }
+        None => {
+            log::debug!("There are no fenced code blocks in a file, defaulting to \"```\"");
+            (CodeFenceStyle::Backtick, 3)
+        }
+    }
+}
+
+/// Detect table style from the first table in a file.
+/// Table is considered aligned when all of its lines has the same display width
+fn detect_table_style(ast: &markdown::mdast::Node, source: &str) -> TableStyle {
+    let first_table =
+        common::ast::BfsIterator::from(ast).find_map(|n| common::ast::try_cast_to_table(n));
+    match first_table {
+        Some(t) => {
+            let mut widths = slice_source(t.position.as_ref(), source)
+                .lines()
+                .map(|line| unicode_width::UnicodeWidthStr::width(line.trim()));
+            let first_width = widths.next().unwrap_or(0);
+            if widths.all(|width| width == first_width) {
+                TableStyle::Aligned
+            } else {
+                TableStyle::Compact
+            }
+        }
+        None => {
+            log::debug!("There are no tables in a file, defaulting to aligned");
+            TableStyle::Aligned
+        }
+    }
+}
+
+/// Detect autolink style from the first autolink or bare link in a file
+fn detect_autolink_style(ast: &markdown::mdast::Node, source: &str) -> AutolinkStyle {
+    let first_autolink = common::ast::BfsIterator::from(ast)
+        .filter_map(|n| common::ast::try_cast_to_link(n))
+        .find(|l| is_auto_or_bare_link(l));
+    match first_autolink {
+        Some(l) if !slice_source(l.position.as_ref(), source).starts_with('<') => {
+            AutolinkStyle::Bare
+        }
+        _ => AutolinkStyle::Angle,
+    }
+}
+
 /// It is possible to pass single "~" and it wold be interpreted
 /// as a delete which shall be interpreted as a superscript
 /// https://github.com/markdown-it/markdown-it-sup
@@ -280,3 +663,40 @@ pub fn is_auto_or_bare_link(l: &markdown::mdast::Link) -> bool {
         false
     }
 }
+
+/// GFM recognizes only "http(s)://" URLs and e-mails as bare links,
+/// everything else has to stay wrapped with angle brackets.
+/// Bare link has to be separated by whitespace, otherwise it absorbs
+/// neighbouring characters, e.g. "<https://a.io>." or "x<https://a.io>"
+pub fn can_be_bare_link(l: &markdown::mdast::Link, source: &str) -> bool {
+    let (before, after) = surrounding_chars(l.position.as_ref(), source);
+    let is_separated = |c: Option<char>| c.map_or(true, char::is_whitespace);
+    (l.url.starts_with("http://") || l.url.starts_with("https://") || l.url.starts_with("mailto:"))
+        && is_separated(before)
+        && is_separated(after)
+}
+
+/// Whether the node touches a letter or a digit, e.g. "foo*bar*baz".
+/// Underscores do not emphasize inside of words, so asterisks have to stay
+pub fn is_intraword(position: Option<&markdown::unist::Position>, source: &str) -> bool {
+    let (before, after) = surrounding_chars(position, source);
+    before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric)
+}
+
+/// Characters right before and right after the node in the source
+fn surrounding_chars(
+    position: Option<&markdown::unist::Position>,
+    source: &str,
+) -> (Option<char>, Option<char>) {
+    match position {
+        Some(position) => (
+            source
+                .get(..position.start.offset)
+                .and_then(|before| before.chars().last()),
+            source
+                .get(position.end.offset..)
+                .and_then(|after| after.chars().next()),
+        ),
+        None => (None, None),
+    }
+}
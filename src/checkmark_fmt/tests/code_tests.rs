@@ -39,3 +39,148 @@ console.log('Hello');
 ",
     );
 }
+
+fn config_with_code_blocks_formatting() -> common::Config {
+    common::Config {
+        fmt: common::FmtConfig {
+            format_code_blocks: true,
+            ..common::FmtConfig::default()
+        },
+        ..common::Config::default()
+    }
+}
+
+/// JSON code block is pretty-printed, order of keys is preserved
+#[test]
+fn code_json() {
+    utils::assert_changed_after_formatting_with_config(
+        "```json
+{\"name\": \"checkmark\",
+      \"tags\": [\"md\", \"fmt\"], \"author\": {\"name\": null}}
+```",
+        "```json
+{
+  \"name\": \"checkmark\",
+  \"tags\": [
+    \"md\",
+    \"fmt\"
+  ],
+  \"author\": {
+    \"name\": null
+  }
+}
+```
+",
+        &config_with_code_blocks_formatting(),
+    );
+
+    // Untouched unless enabled
+    utils::assert_unchanged_after_formatting(
+        "```json
+{\"name\": \"checkmark\"}
+```
+",
+    );
+}
+
+/// Numbers in JSON code blocks are kept exactly as written
+#[test]
+fn code_json_numbers() {
+    utils::assert_changed_after_formatting_with_config(
+        "```json
+{\"exp\": 1e3, \"price\": 1.50, \"id\": 12345678901234567890123, \"empty\": [ ]}
+```",
+        "```json
+{
+  \"exp\": 1e3,
+  \"price\": 1.50,
+  \"id\": 12345678901234567890123,
+  \"empty\": []
+}
+```
+",
+        &config_with_code_blocks_formatting(),
+    );
+}
+
+/// TOML code block is normalized, comments are preserved
+#[test]
+fn code_toml() {
+    utils::assert_changed_after_formatting_with_config(
+        "```toml
+# Package
+  [ package ]
+  name   =  \"checkmark\"   # Name
+
+
+  version=\"0.6.0\"
+  deps = {a=1,b=2}
+[[bin]]
+name='checkmark'
+```",
+        "```toml
+# Package
+[package]
+name = \"checkmark\" # Name
+
+version = \"0.6.0\"
+deps = { a = 1, b = 2 }
+[[bin]]
+name = 'checkmark'
+```
+",
+        &config_with_code_blocks_formatting(),
+    );
+}
+
+/// Invalid code block is left untouched and reported
+#[test]
+fn code_invalid_reported() {
+    let original = utils::create_dummy_md_file(
+        "# Invalid
+
+```json
+{\"name\": }
+```
+",
+    );
+    let formatted = checkmark_fmt::fmt_markdown(&original, &config_with_code_blocks_formatting());
+    assert_eq!(original.content, formatted.content);
+    assert_eq!(1, formatted.issues.len());
+    assert_eq!(3, formatted.issues[0].row_num_start);
+    assert_eq!(common::IssueSeverity::Warning, formatted.issues[0].severity);
+}
+
+/// Invalid code block is reported at its position in the original file
+/// which has BOM and CRLF line endings
+#[test]
+fn code_invalid_reported_at_original_position() {
+    let original =
+        utils::create_dummy_md_file("\u{FEFF}# Invalid\r\n\r\n```json\r\n{\"name\": }\r\n```\r\n");
+    let formatted = checkmark_fmt::fmt_markdown(&original, &config_with_code_blocks_formatting());
+    assert_eq!(1, formatted.issues.len());
+    assert_eq!(3, formatted.issues[0].row_num_start);
+    assert_eq!(
+        "```json\r\n{\"name\": }\r\n```",
+        &original.content[formatted.issues[0].offset_start..formatted.issues[0].offset_end]
+    );
+}
+
+/// Code block is left untouched and reported when external formatter can not be run
+#[test]
+fn code_external_formatter_failed() {
+    let mut config = common::Config::default();
+    config.fmt.code_blocks.insert(
+        String::from("rust"),
+        String::from("checkmark-non-existing-formatter --emit stdout"),
+    );
+    let original = utils::create_dummy_md_file(
+        "```rust
+fn main(){}
+```
+",
+    );
+    let formatted = checkmark_fmt::fmt_markdown(&original, &config);
+    assert_eq!(original.content, formatted.content);
+    assert_eq!(1, formatted.issues.len());
+}
@@ -66,3 +66,26 @@ fn heading_mixed_depth_ok() {
 fn heading_invalid_level_not_ignored() {
     utils::assert_unchanged_after_formatting("####### This is not an H7\n");
 }
+
+/// Section numbers are updated and table of contents reflects them
+#[test]
+fn heading_numbering() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Spec\n\n<!-- toc -->\n<!-- tocstop -->\n\n## Errors\n\n### 2.1 Handling\n\n## 1 Intro\n",
+        "# Spec\n\n<!-- toc -->\n\n- [1 Errors](#1-errors)\n  - [1.1 Handling](#11-handling)\n- [2 Intro](#2-intro)\n\n<!-- tocstop -->\n\n## 1 Errors\n\n### 1.1 Handling\n\n## 2 Intro\n",
+        &common::Config {
+            style: common::StyleConfig {
+                heading_numbering: common::HeadingNumberingConfig {
+                    style: common::HeadingNumberingStyle::Numbered,
+                    start_depth: 2,
+                },
+                toc: common::TocConfig {
+                    min_depth: 2,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+}
@@ -68,3 +68,34 @@ Example paragraph that follows html.
 ",
     );
 }
+
+/// Table of contents between markers is regenerated
+#[test]
+fn html_toc_regenerated() {
+    utils::assert_changed_after_formatting(
+        "# Title
+
+<!-- toc -->
+- [Old](#old)
+<!-- tocstop -->
+
+## One
+
+### Two
+",
+        "# Title
+
+<!-- toc -->
+
+- [Title](#title)
+  - [One](#one)
+    - [Two](#two)
+
+<!-- tocstop -->
+
+## One
+
+### Two
+",
+    );
+}
@@ -0,0 +1,49 @@
+mod utils;
+
+fn config_with_line_endings(line_endings: common::LineEndings) -> common::Config {
+    common::Config {
+        fmt: common::FmtConfig {
+            line_endings,
+            ..common::FmtConfig::default()
+        },
+        ..common::Config::default()
+    }
+}
+
+/// CRLF document stays CRLF
+#[test]
+fn crlf_preserved() {
+    utils::assert_unchanged_after_formatting("# Heading\r\n\r\n- One\r\n- Two\r\n");
+    utils::assert_changed_after_formatting(
+        "# Heading\r\n\r\n\r\nText\r\n",
+        "# Heading\r\n\r\nText\r\n",
+    );
+}
+
+/// Dominant line ending wins in a document with mixed line endings
+#[test]
+fn mixed_line_endings_normalized_to_dominant() {
+    utils::assert_changed_after_formatting("# Heading\r\n\r\nText\n", "# Heading\r\n\r\nText\r\n");
+}
+
+/// Line endings could be forced
+#[test]
+fn line_endings_forced() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Heading\r\n\r\nText\r\n",
+        "# Heading\n\nText\n",
+        &config_with_line_endings(common::LineEndings::Lf),
+    );
+    utils::assert_changed_after_formatting_with_config(
+        "# Heading\n\nText\n",
+        "# Heading\r\n\r\nText\r\n",
+        &config_with_line_endings(common::LineEndings::Crlf),
+    );
+}
+
+/// UTF-8 BOM is preserved
+#[test]
+fn bom_preserved() {
+    utils::assert_unchanged_after_formatting("\u{FEFF}# Heading\n\nText\n");
+    utils::assert_unchanged_after_formatting("\u{FEFF}# Heading\r\n\r\nText\r\n");
+}
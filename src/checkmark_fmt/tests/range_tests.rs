@@ -0,0 +1,87 @@
+mod utils;
+
+#[cfg(test)]
+use pretty_assertions::assert_eq;
+
+fn fmt_range(source: &str, lines: std::ops::RangeInclusive<usize>) -> String {
+    let file = utils::create_dummy_md_file(source);
+    checkmark_fmt::fmt_markdown_range(&file, &common::Config::default(), &lines).content
+}
+
+/// Only blocks intersecting with the range are formatted
+#[test]
+fn range_formats_only_intersecting_blocks() {
+    let source = "#   Heading\n\n\n*  One\n*  Two\n\n\n+  Three\n+  Four\nText   \n";
+    assert_eq!(
+        "#   Heading\n\n\n* One\n* Two\n\n\n+  Three\n+  Four\nText   \n",
+        fmt_range(source, 4..=4)
+    );
+    assert_eq!(
+        "# Heading\n\n\n* One\n* Two\n\n\n+  Three\n+  Four\nText   \n",
+        fmt_range(source, 1..=5)
+    );
+}
+
+/// Nothing is changed when range does not intersect with any block
+#[test]
+fn range_outside_of_blocks() {
+    let source = "#   Heading\n\n\n\nText   \n";
+    assert_eq!(source, fmt_range(source, 3..=3));
+    assert_eq!(source, fmt_range(source, 100..=200));
+}
+
+/// Line endings and BOM outside of the range are preserved
+#[test]
+fn range_preserves_bytes_outside() {
+    let source = "\u{FEFF}#   Heading\r\n\r\n\r\nText\r\n\r\n\r\n*  One\r\n";
+    assert_eq!(
+        "\u{FEFF}# Heading\r\n\r\n\r\nText\r\n\r\n\r\n*  One\r\n",
+        fmt_range(source, 1..=1)
+    );
+}
+
+/// Code blocks outside of the range are neither formatted nor reported
+#[test]
+fn range_formats_only_code_blocks_in_range() {
+    let mut config = common::Config::default();
+    config.fmt.format_code_blocks = true;
+    config.fmt.code_blocks.insert(
+        String::from("rust"),
+        String::from("checkmark-non-existing-formatter"),
+    );
+    let source = "```json\n{ \"a\":1 }\n```\n\n```rust\nfn main(){}\n```\n";
+    let file = utils::create_dummy_md_file(source);
+    let formatted = checkmark_fmt::fmt_markdown_range(&file, &config, &(1..=3));
+    assert_eq!(
+        "```json\n{\n  \"a\": 1\n}\n```\n\n```rust\nfn main(){}\n```\n",
+        formatted.content
+    );
+    assert!(formatted.issues.is_empty());
+}
+
+/// Heading numbers and table of contents are updated within the range
+#[test]
+fn range_updates_numbering_and_toc() {
+    let mut config = common::Config::default();
+    config.style.heading_numbering.style = common::HeadingNumberingStyle::Numbered;
+    config.style.heading_numbering.start_depth = 2;
+    let source = "# Title\n\n<!-- toc -->\n<!-- tocstop -->\n\n## Intro\n\n## Usage\n";
+    let file = utils::create_dummy_md_file(source);
+    assert_eq!(
+        "# Title\n\n<!-- toc -->\n\n- [Title](#title)\n  - [1 Intro](#1-intro)\n  - [2 Usage](#2-usage)\n\n<!-- tocstop -->\n\n## 1 Intro\n\n## Usage\n",
+        checkmark_fmt::fmt_markdown_range(&file, &config, &(3..=6)).content
+    );
+}
+
+/// Links in range are converted to references together with their definitions
+#[test]
+fn range_converts_links_to_references() {
+    let mut config = common::Config::default();
+    config.fmt.references.links = common::LinkReferenceStyle::Reference;
+    let source = "See [docs](https://docs.rs).\n\nSee [site](https://site.com).\n";
+    let file = utils::create_dummy_md_file(source);
+    assert_eq!(
+        "See [docs][docs].\n\n[docs]: https://docs.rs\n\nSee [site](https://site.com).\n",
+        checkmark_fmt::fmt_markdown_range(&file, &config, &(1..=1)).content
+    );
+}
@@ -0,0 +1,157 @@
+mod utils;
+
+fn config_with_references(references: common::ReferencesConfig) -> common::Config {
+    common::Config {
+        fmt: common::FmtConfig {
+            references,
+            ..common::FmtConfig::default()
+        },
+        ..common::Config::default()
+    }
+}
+
+/// Inline links are converted to references, same URL shares a definition
+#[test]
+fn references_from_inline_links() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Title
+
+See [Checkmark](https://github.com/vvvar/checkmark) and [Example](https://example.com \"Title\").
+
+Again [Checkmark](https://github.com/vvvar/checkmark), [top](#title) and <https://example.com>.
+",
+        "# Title
+
+See [Checkmark][checkmark] and [Example][example].
+
+Again [Checkmark][checkmark], [top](#title) and <https://example.com>.
+
+[checkmark]: https://github.com/vvvar/checkmark
+[example]: https://example.com \"Title\"
+",
+        &config_with_references(common::ReferencesConfig {
+            links: common::LinkReferenceStyle::Reference,
+            ..common::ReferencesConfig::default()
+        }),
+    );
+}
+
+/// References are converted to inline links, their definitions are dropped
+#[test]
+fn references_to_inline_links() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Title
+
+See [Checkmark][checkmark] and ![Logo][logo].
+
+[checkmark]: https://github.com/vvvar/checkmark
+[logo]: ./logo.png
+[unused]: https://example.com
+",
+        "# Title
+
+See [Checkmark](https://github.com/vvvar/checkmark) and ![Logo][logo].
+
+[logo]: ./logo.png
+[unused]: https://example.com
+",
+        &config_with_references(common::ReferencesConfig {
+            links: common::LinkReferenceStyle::Inline,
+            ..common::ReferencesConfig::default()
+        }),
+    );
+}
+
+/// Definitions are collected at the end of the document, sorted, normalized and unused are dropped
+#[test]
+fn references_collected_at_the_end_of_document() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Title
+
+[Zeta Link]: https://zeta.com
+
+Text with [zeta][Zeta   Link] and [alpha][ALPHA].
+
+[ALPHA]: https://alpha.com
+[unused]: https://unused.com
+
+## Section
+
+Text.
+",
+        "# Title
+
+Text with [zeta][zeta link] and [alpha][alpha].
+
+## Section
+
+Text.
+
+[alpha]: https://alpha.com
+[zeta link]: https://zeta.com
+",
+        &config_with_references(common::ReferencesConfig {
+            definitions: common::DefinitionsPlacement::Document,
+            sort: true,
+            normalize_labels: true,
+            remove_unused: true,
+            ..common::ReferencesConfig::default()
+        }),
+    );
+}
+
+/// Definitions are collected at the end of the section where they are used first
+#[test]
+fn references_collected_at_the_end_of_section() {
+    utils::assert_changed_after_formatting_with_config(
+        "# Title
+
+Text with [b][b] and [a][a].
+
+## Section
+
+Text with [c][c] and [a][a].
+
+[a]: https://a.com
+[b]: https://b.com
+[c]: https://c.com
+[unused]: https://unused.com
+",
+        "# Title
+
+Text with [b][b] and [a][a].
+
+[a]: https://a.com
+[b]: https://b.com
+
+## Section
+
+Text with [c][c] and [a][a].
+
+[c]: https://c.com
+[unused]: https://unused.com
+",
+        &config_with_references(common::ReferencesConfig {
+            definitions: common::DefinitionsPlacement::Section,
+            sort: true,
+            ..common::ReferencesConfig::default()
+        }),
+    );
+}
+
+/// Generated labels are escaped and fit into the max label length, result is stable
+#[test]
+fn references_with_brackets_and_long_text() {
+    let config = config_with_references(common::ReferencesConfig {
+        links: common::LinkReferenceStyle::Reference,
+        ..common::ReferencesConfig::default()
+    });
+    let long_text = "a".repeat(1200);
+    let source = format!("See [a [b] c](https://a.io) and [{long_text}](https://b.io).\n");
+    let expected = format!(
+        "See [a \\[b\\] c][a \\[b\\] c] and [{long_text}][{label}].\n\n[a \\[b\\] c]: https://a.io\n[{label}]: https://b.io\n",
+        label = "a".repeat(999)
+    );
+    utils::assert_changed_after_formatting_with_config(&source, &expected, &config);
+    utils::assert_unchanged_after_formatting_with_config(&expected, &config);
+}
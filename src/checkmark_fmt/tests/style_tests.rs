@@ -0,0 +1,140 @@
+mod utils;
+
+fn config_with_style(style: common::StyleConfig) -> common::Config {
+    common::Config {
+        style,
+        ..common::Config::default()
+    }
+}
+
+/// Emphasis style is inferred from the first emphasis or forced
+#[test]
+fn emphasis_style() {
+    utils::assert_unchanged_after_formatting("_One_ and _two_\n");
+    utils::assert_changed_after_formatting("_One_ and *two*\n", "_One_ and _two_\n");
+    utils::assert_changed_after_formatting_with_config(
+        "_One_ and *two*\n",
+        "*One* and *two*\n",
+        &config_with_style(common::StyleConfig {
+            emphasis: common::EmphasisStyle::Asterisk,
+            ..Default::default()
+        }),
+    );
+    // Underscores do not emphasize inside of words
+    utils::assert_changed_after_formatting(
+        "_One_ and foo*bar*baz *two*\n",
+        "_One_ and foo*bar*baz _two_\n",
+    );
+}
+
+/// Thematic break is inferred from the first one or forced
+#[test]
+fn thematic_break_style() {
+    utils::assert_unchanged_after_formatting("One\n\n* * *\n\nTwo\n");
+    utils::assert_changed_after_formatting(
+        "One\n\n***\n\nTwo\n\n---\n",
+        "One\n\n***\n\nTwo\n\n***\n",
+    );
+    utils::assert_changed_after_formatting_with_config(
+        "One\n\n***\n\nTwo\n",
+        "One\n\n___\n\nTwo\n",
+        &config_with_style(common::StyleConfig {
+            thematic_break: common::ThematicBreakStyle::Underscore,
+            ..Default::default()
+        }),
+    );
+}
+
+/// Code fence character and length are inferred from the first fenced code block or forced
+#[test]
+fn code_fence_style() {
+    utils::assert_unchanged_after_formatting("~~~~js\nconsole.log('Hello');\n~~~~\n");
+    utils::assert_changed_after_formatting_with_config(
+        "```js\nconsole.log('Hello');\n```\n",
+        "~~~js\nconsole.log('Hello');\n~~~\n",
+        &config_with_style(common::StyleConfig {
+            code_fence: common::CodeFenceStyle::Tilde,
+            ..Default::default()
+        }),
+    );
+    // Fence is extended when code contains a fence-like sequence
+    utils::assert_unchanged_after_formatting("````md\n```js\n```\n````\n");
+}
+
+/// Ordered list numbering and delimiter are inferred from the first list or forced
+#[test]
+fn ordered_list_style() {
+    utils::assert_unchanged_after_formatting("1. One\n1. Two\n1. Three\n");
+    utils::assert_unchanged_after_formatting("1) One\n2) Two\n3) Three\n");
+    utils::assert_changed_after_formatting_with_config(
+        "1. One\n2. Two\n3. Three\n",
+        "1) One\n1) Two\n1) Three\n",
+        &config_with_style(common::StyleConfig {
+            ordered_lists: common::OrderedListStyle::One,
+            ordered_list_delimiter: common::OrderedListDelimiterStyle::Parenthesis,
+            ..Default::default()
+        }),
+    );
+}
+
+/// Tables could be rendered compact
+#[test]
+fn table_style() {
+    utils::assert_changed_after_formatting_with_config(
+        "| Syntax    | Description |\n| :-------- | ----------: |\n| Header    | Title       |\n",
+        "| Syntax | Description |\n| :-- | --: |\n| Header | Title |\n",
+        &config_with_style(common::StyleConfig {
+            tables: common::TableStyle::Compact,
+            ..Default::default()
+        }),
+    );
+    utils::assert_unchanged_after_formatting_with_config(
+        "| Syntax | Description |\n| :-- | --: |\n| Header | Title |\n",
+        &config_with_style(common::StyleConfig {
+            tables: common::TableStyle::Consistent,
+            ..Default::default()
+        }),
+    );
+}
+
+/// Autolinks could be rendered bare
+#[test]
+fn autolink_style() {
+    utils::assert_changed_after_formatting_with_config(
+        "<https://github.com> and <someone@some.where>\n",
+        "https://github.com and someone@some.where\n",
+        &config_with_style(common::StyleConfig {
+            autolinks: common::AutolinkStyle::Bare,
+            ..Default::default()
+        }),
+    );
+    // Bare link would absorb characters next to it
+    utils::assert_unchanged_after_formatting_with_config(
+        "See <https://github.com>. Or x<https://github.com>\n",
+        &config_with_style(common::StyleConfig {
+            autolinks: common::AutolinkStyle::Bare,
+            ..Default::default()
+        }),
+    );
+}
+
+/// Blank lines between list items could be forced
+#[test]
+fn list_item_spacing_style() {
+    utils::assert_changed_after_formatting_with_config(
+        "- One\n\n- Two\n\n- Three\n",
+        "- One\n- Two\n- Three\n",
+        &config_with_style(common::StyleConfig {
+            list_item_spacing: common::ListItemSpacingStyle::Tight,
+            ..Default::default()
+        }),
+    );
+    utils::assert_changed_after_formatting_with_config(
+        "- One\n- Two\n",
+        "- One\n\n- Two\n",
+        &config_with_style(common::StyleConfig {
+            list_item_spacing: common::ListItemSpacingStyle::Loose,
+            ..Default::default()
+        }),
+    );
+}
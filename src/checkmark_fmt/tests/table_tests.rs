@@ -35,3 +35,54 @@ fn table_with_special_symbols() {
 ",
     );
 }
+
+/// Wide(CJK, emoji) and accented characters are measured in display columns
+#[test]
+fn table_with_wide_characters() {
+    utils::assert_changed_after_formatting(
+        "| Name | Description |
+| --- | --- |
+| 日本語 | Café |
+| 🚀 | Rocket |
+",
+        "| Name   | Description |
+| ------ | ----------- |
+| 日本語 | Café        |
+| 🚀     | Rocket      |
+",
+    );
+}
+
+/// Empty column is rendered with the minimal separator
+#[test]
+fn table_with_empty_column() {
+    utils::assert_changed_after_formatting(
+        "| | A |\n| :-: | - |\n| | b |\n",
+        "|     | A |\n| :-: | - |\n|     | b |\n",
+    );
+}
+
+/// Pipes stay escaped in text and inline code
+#[test]
+fn table_with_escaped_pipes() {
+    utils::assert_changed_after_formatting(
+        "| Operator | Example |\n| --- | --- |\n| or | `a \\| b` |\n| pipe | \\| |\n",
+        "| Operator | Example  |\n| -------- | -------- |\n| or       | `a \\| b` |\n| pipe     | \\|       |\n",
+    );
+}
+
+/// Cells wider than the limit are not padded
+#[test]
+fn table_max_column_width() {
+    utils::assert_changed_after_formatting_with_config(
+        "| Key | Value |\n| --- | --- |\n| a | This is a very long value |\n| b | c |\n",
+        "| Key | Value      |\n| --- | ---------- |\n| a   | This is a very long value |\n| b   | c          |\n",
+        &common::Config {
+            style: common::StyleConfig {
+                max_table_column_width: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+}
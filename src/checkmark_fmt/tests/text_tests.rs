@@ -22,3 +22,57 @@ fn hard_tabs() {
         "# Text with  hard tabs\n",
     );
 }
+
+fn config_with_typography() -> common::Config {
+    common::Config {
+        fmt: common::FmtConfig {
+            typography: common::TypographyConfig {
+                quotes: common::QuotesStyle::Curly,
+                dashes: true,
+                ellipsis: true,
+                nbsp_before_units: true,
+                single_space_after_period: true,
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Typography is normalized in text only
+#[test]
+fn typography() {
+    utils::assert_changed_after_formatting_with_config(
+        "\"Wait...\" -- it's 10 kg.  Run `a -- \"b\"` or --help\n",
+        "“Wait…” – it’s 10\u{a0}kg. Run `a -- \"b\"` or --help\n",
+        &config_with_typography(),
+    );
+}
+
+/// Escaped quotes and dashes are written back escaped, so formatting is stable
+/// and formatted document has nothing to normalize
+#[test]
+fn typography_keeps_escapes() {
+    let config = config_with_typography();
+    let source = "Say \\\"hi\\\" or &quot;bye&quot;, use \\-\\- and \"quotes\".\n";
+    let once = checkmark_fmt::fmt_markdown(&utils::create_dummy_md_file(source), &config);
+    assert_eq!(
+        "Say \\\"hi\\\" or &quot;bye&quot;, use \\-\\- and “quotes”.\n",
+        once.content
+    );
+    let twice = checkmark_fmt::fmt_markdown(&once, &config);
+    assert_eq!(once.content, twice.content);
+    let ast = common::ast::parse(&twice.content).unwrap();
+    for check in [
+        common::typography::Check::Quotes,
+        common::typography::Check::Dashes,
+        common::typography::Check::Ellipsis,
+        common::typography::Check::UnitSpaces,
+        common::typography::Check::SentenceSpaces,
+    ] {
+        assert_eq!(
+            Vec::<common::typography::Issue>::new(),
+            common::typography::check(&ast, &twice.content, check, &config.fmt.typography)
+        );
+    }
+}
@@ -28,3 +28,25 @@ pub fn assert_changed_after_formatting(source: &str, expected: &str) {
     let formatted = checkmark_fmt::fmt_markdown(&original, &common::Config::default());
     assert_eq!(&expected, &formatted.content);
 }
+
+/// Take markdown as an input, perform fmt with a custom config and
+/// check there's formatted matches expectation
+#[allow(dead_code)]
+pub fn assert_changed_after_formatting_with_config(
+    source: &str,
+    expected: &str,
+    config: &common::Config,
+) {
+    let original = create_dummy_md_file(source);
+    let formatted = checkmark_fmt::fmt_markdown(&original, config);
+    assert_eq!(&expected, &formatted.content);
+}
+
+/// Take markdown as an input, perform fmt with a custom config and
+/// check there's no diff between input and fmt
+#[allow(dead_code)]
+pub fn assert_unchanged_after_formatting_with_config(markdown: &str, config: &common::Config) {
+    let original = create_dummy_md_file(markdown);
+    let formatted = checkmark_fmt::fmt_markdown(&original, config);
+    assert_eq!(&original.content, &formatted.content);
+}
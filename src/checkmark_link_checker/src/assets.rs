@@ -0,0 +1,150 @@
+use common::AssetsConfig;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use wildmatch::WildMatch;
+
+/// Actual path of the file when the link differs from it only by case of letters,
+/// e.g. "assets/logo.png" for "assets/Logo.PNG". Such links work on case-insensitive
+/// file systems(macOS, Windows), but are broken on Linux and most web servers.
+/// None when path matches exactly or there is no such file at all
+pub fn find_case_mismatch(path: &Path) -> Option<PathBuf> {
+    let mut actual = PathBuf::new();
+    let mut is_mismatch = false;
+    for component in path.components() {
+        let name = match component {
+            Component::Normal(name) => name,
+            other => {
+                actual.push(other);
+                continue;
+            }
+        };
+        let names = std::fs::read_dir(&actual)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.file_name()))
+            .collect::<Vec<_>>();
+        if names.iter().any(|candidate| candidate == name) {
+            actual.push(name);
+            continue;
+        }
+        let lowercase = name.to_string_lossy().to_lowercase();
+        let found = names
+            .into_iter()
+            .find(|candidate| candidate.to_string_lossy().to_lowercase() == lowercase)?;
+        actual.push(found);
+        is_mismatch = true;
+    }
+    is_mismatch.then_some(actual)
+}
+
+/// Files in the asset directories that are not linked from any document.
+/// Link to a directory uses every file in it, unless it is an asset directory
+/// or one of its parents(e.g. "./" or ".."), which would use all of them.
+/// Markdown files are documents, not assets
+pub fn find_unused(
+    config: &AssetsConfig,
+    root_dir: &Path,
+    referenced: &HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+    let ignored = config
+        .ignore
+        .iter()
+        .map(|pattern| WildMatch::new(pattern))
+        .collect::<Vec<WildMatch>>();
+    let mut files: Vec<PathBuf> = vec![];
+    for dir in &config.dirs {
+        list_files(&root_dir.join(dir), &mut files);
+    }
+    let asset_dirs = config
+        .dirs
+        .iter()
+        .filter_map(|dir| std::fs::canonicalize(root_dir.join(dir)).ok())
+        .collect::<Vec<PathBuf>>();
+    let referenced = referenced
+        .iter()
+        .filter(|path| !asset_dirs.iter().any(|dir| dir.starts_with(path)))
+        .collect::<Vec<&PathBuf>>();
+    files.sort();
+    files.dedup();
+    files
+        .into_iter()
+        .filter(|file| {
+            !file
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .filter(|file| {
+            let canonical = std::fs::canonicalize(file).unwrap_or(file.clone());
+            !referenced.iter().any(|path| canonical.starts_with(path))
+        })
+        .filter(|file| {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            let relative = file
+                .strip_prefix(root_dir)
+                .unwrap_or(file)
+                .to_string_lossy();
+            !ignored
+                .iter()
+                .any(|pattern| pattern.matches(&name) || pattern.matches(&relative))
+        })
+        .collect()
+}
+
+fn list_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            log::warn!("Unable to read asset directory {dir:#?}: {error}");
+            return;
+        }
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if path.is_dir() {
+            list_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_mismatch_and_unused_assets_found() {
+        let root = std::env::temp_dir().join(format!("checkmark_assets_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("assets/icons")).unwrap();
+        for file in ["assets/logo.png", "assets/unused.png", "assets/icons/a.ico"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        assert_eq!(
+            Some(root.join("assets/logo.png")),
+            find_case_mismatch(&root.join("Assets/Logo.PNG"))
+        );
+        assert_eq!(None, find_case_mismatch(&root.join("assets/logo.png")));
+        assert_eq!(None, find_case_mismatch(&root.join("assets/missing.png")));
+
+        let config = AssetsConfig {
+            dirs: vec![String::from("assets")],
+            ignore: vec![String::from("*.ico")],
+        };
+        let referenced =
+            HashSet::from([std::fs::canonicalize(root.join("assets/logo.png")).unwrap()]);
+        assert_eq!(
+            vec![root.join("assets/unused.png")],
+            find_unused(&config, &root, &referenced)
+        );
+        // Links to the root or to the asset directory itself do not use every asset
+        let referenced = HashSet::from([
+            std::fs::canonicalize(&root).unwrap(),
+            std::fs::canonicalize(root.join("assets")).unwrap(),
+        ]);
+        assert_eq!(
+            vec![root.join("assets/logo.png"), root.join("assets/unused.png")],
+            find_unused(&config, &root, &referenced)
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+use common::LinkCacheConfig;
+use log::debug;
+use lychee_lib::{CacheStatus, ErrorKind, Status, Uri};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Result of a link check from one of the previous runs
+#[derive(Debug, Clone, PartialEq)]
+struct CacheEntry {
+    status: CacheStatus,
+    /// Seconds since UNIX epoch when link was checked
+    timestamp: u64,
+    /// Link was checked for redirects and is not redirected
+    not_redirected: bool,
+}
+
+/// Results of link checks persisted between runs, so the same URL
+/// is not requested again until its result expires.
+/// Stored as a text file where each line is "<timestamp>\t<status>\t<redirect>\t<uri>",
+/// where redirect is "none" when the link is known to be not redirected and "unknown" otherwise
+pub struct LinkCache {
+    config: LinkCacheConfig,
+    entries: HashMap<String, CacheEntry>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Key of the cache: URI without fragment and trailing slash
+fn normalize_uri(uri: &Uri) -> String {
+    let mut url = uri.url.clone();
+    url.set_fragment(None);
+    url.as_str().trim_end_matches('/').to_string()
+}
+
+/// Only web links are cached, files and e-mails are cheap to check
+fn is_cacheable(uri: &Uri) -> bool {
+    matches!(uri.scheme(), "http" | "https")
+}
+
+fn serialize_status(status: &CacheStatus) -> Option<String> {
+    match status {
+        CacheStatus::Ok(code) => Some(code.to_string()),
+        CacheStatus::Error(Some(code)) => Some(format!("error:{code}")),
+        CacheStatus::Error(None) => Some(String::from("error")),
+        _ => None,
+    }
+}
+
+fn deserialize_status(status: &str) -> Option<CacheStatus> {
+    match status.strip_prefix("error") {
+        Some("") => Some(CacheStatus::Error(None)),
+        Some(code) => code
+            .strip_prefix(':')
+            .and_then(|code| code.parse().ok())
+            .map(|code| CacheStatus::Error(Some(code))),
+        None => status.parse().ok().map(CacheStatus::Ok),
+    }
+}
+
+/// Status that is worth remembering. Timeouts and errors
+/// without HTTP status code are transient, so they are not cached
+fn to_cache_status(status: &Status) -> Option<CacheStatus> {
+    match status {
+        Status::Ok(code) | Status::Redirected(code) => Some(CacheStatus::Ok(code.as_u16())),
+        Status::Error(ErrorKind::NetworkRequest(error)) => error
+            .status()
+            .map(|code| CacheStatus::Error(Some(code.as_u16()))),
+        _ => None,
+    }
+}
+
+impl LinkCache {
+    /// Read cache from the file. Cache is empty when it is disabled,
+    /// refresh is requested or file does not exist(yet)
+    pub fn load(config: &LinkCacheConfig) -> Self {
+        let mut cache = Self {
+            config: config.clone(),
+            entries: HashMap::new(),
+        };
+        if !config.enabled || config.refresh {
+            return cache;
+        }
+        let content = match std::fs::read_to_string(&config.path) {
+            Ok(content) => content,
+            Err(error) => {
+                debug!("Unable to read link cache {:#?}: {error}", &config.path);
+                return cache;
+            }
+        };
+        for line in content.lines() {
+            let columns = line.splitn(4, '\t').collect::<Vec<&str>>();
+            // Lines without redirect column are written by older versions
+            let (timestamp, status, redirect, uri) = match columns[..] {
+                [timestamp, status, uri] => (timestamp, status, "unknown", uri),
+                [timestamp, status, redirect, uri] => (timestamp, status, redirect, uri),
+                _ => {
+                    debug!("Skipping malformed line of link cache: {line:#?}");
+                    continue;
+                }
+            };
+            let entry = timestamp.parse().ok().zip(deserialize_status(status)).map(
+                |(timestamp, status)| {
+                    let entry = CacheEntry {
+                        status,
+                        timestamp,
+                        not_redirected: redirect == "none",
+                    };
+                    (uri, entry)
+                },
+            );
+            match entry {
+                Some((uri, entry)) => {
+                    cache.entries.insert(uri.to_string(), entry);
+                }
+                None => debug!("Skipping malformed line of link cache: {line:#?}"),
+            }
+        }
+        debug!("Loaded {} entries from link cache", cache.entries.len());
+        cache
+    }
+
+    /// How long the result stays valid
+    fn ttl(&self, status: &CacheStatus) -> u64 {
+        let ttl = match status {
+            CacheStatus::Ok(_) => self.config.success_ttl,
+            _ => self.config.failure_ttl,
+        };
+        ttl.min(self.config.max_age)
+    }
+
+    /// Return result of the previous check when it has not expired yet.
+    /// When redirects are followed, only results of links that are known
+    /// to be not redirected are returned, so redirects are not missed
+    pub fn get(&self, uri: &Uri, follows_redirects: bool) -> Option<CacheStatus> {
+        if !self.config.enabled || !is_cacheable(uri) {
+            return None;
+        }
+        self.entries
+            .get(&normalize_uri(uri))
+            .filter(|entry| now().saturating_sub(entry.timestamp) < self.ttl(&entry.status))
+            .filter(|entry| entry.not_redirected || !follows_redirects)
+            .map(|entry| entry.status)
+    }
+
+    /// Remember the result of a check. `not_redirected` is set when
+    /// the link was checked for redirects and it is not redirected
+    pub fn insert(&mut self, uri: &Uri, status: &Status, not_redirected: bool) {
+        if !self.config.enabled || !is_cacheable(uri) {
+            return;
+        }
+        if let Some(status) = to_cache_status(status) {
+            self.entries.insert(
+                normalize_uri(uri),
+                CacheEntry {
+                    status,
+                    timestamp: now(),
+                    not_redirected,
+                },
+            );
+        }
+    }
+
+    /// Write cache to the file. Entries older than max age are dropped
+    pub fn save(&self) {
+        if !self.config.enabled {
+            return;
+        }
+        let now = now();
+        let mut lines = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| now.saturating_sub(entry.timestamp) < self.config.max_age)
+            .filter_map(|(uri, entry)| {
+                let redirect = match entry.not_redirected {
+                    true => "none",
+                    false => "unknown",
+                };
+                serialize_status(&entry.status)
+                    .map(|status| format!("{}\t{status}\t{redirect}\t{uri}\n", entry.timestamp))
+            })
+            .collect::<Vec<String>>();
+        lines.sort();
+        let path = std::path::Path::new(&self.config.path);
+        if let Some(dir) = path.parent() {
+            if let Err(error) = std::fs::create_dir_all(dir) {
+                log::warn!("Unable to create link cache directory {dir:#?}: {error}");
+                return;
+            }
+        }
+        match std::fs::write(path, lines.concat()) {
+            Ok(_) => debug!("Saved {} entries to link cache {path:#?}", lines.len()),
+            Err(error) => log::warn!("Unable to write link cache {path:#?}: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_without_redirect_info_are_missed_when_redirects_are_followed() {
+        let path = std::env::temp_dir().join(format!("checkmark_cache_{}", std::process::id()));
+        let now = now();
+        std::fs::write(
+            &path,
+            format!(
+                "{now}\t200\thttps://a.com\n{now}\t200\tnone\thttps://b.com\n{now}\t200\tunknown\thttps://c.com\n"
+            ),
+        )
+        .unwrap();
+        let cache = LinkCache::load(&LinkCacheConfig {
+            enabled: true,
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        std::fs::remove_file(&path).unwrap();
+        let uri = |uri: &str| Uri::try_from(uri).unwrap();
+        for (uri, follows_redirects, expected) in [
+            (uri("https://a.com"), false, Some(CacheStatus::Ok(200))),
+            (uri("https://a.com"), true, None),
+            (uri("https://b.com/"), true, Some(CacheStatus::Ok(200))),
+            (uri("https://c.com"), false, Some(CacheStatus::Ok(200))),
+            (uri("https://c.com"), true, None),
+        ] {
+            assert_eq!(expected, cache.get(&uri, follows_redirects));
+        }
+    }
+}
@@ -1,9 +1,27 @@
 use std::collections::HashSet;
 use std::time::Duration;
 
-use common::Config;
-use reqwest::StatusCode;
-use secrecy::SecretString;
+use crate::cookies::{self, Cookie};
+use base64::Engine;
+use common::{Config, HostAuth, NetworkConfig};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{StatusCode, Url};
+use secrecy::{ExposeSecret, SecretString};
+use wildmatch::WildMatch;
+
+/// Headers sent with requests to hosts that match the pattern
+pub struct HostHeaders {
+    pub pattern: WildMatch,
+    pub headers: Vec<(HeaderName, SecretString)>,
+}
+
+/// Host rule and cookies that apply to a URL.
+/// Requests with the same key are sent with the same headers
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeadersKey {
+    host: Option<usize>,
+    cookies: Vec<usize>,
+}
 
 pub struct ClientConfig {
     pub accepted_status_codes: HashSet<StatusCode>,
@@ -13,6 +31,14 @@ pub struct ClientConfig {
     pub check_emails: bool,
     pub user_agent: String,
     pub allow_insecure: bool,
+    pub max_concurrency: usize,
+    pub max_concurrency_per_host: usize,
+    pub retry_base_delay: Duration,
+    pub retry_max_delay: Duration,
+    pub min_delay_per_host: Duration,
+    pub hosts: Vec<HostHeaders>,
+    pub cookies: Vec<Cookie>,
+    pub network: NetworkConfig,
 }
 
 impl ClientConfig {
@@ -25,9 +51,114 @@ impl ClientConfig {
             check_emails: config.link_checker.check_emails,
             user_agent: ClientConfig::user_agent(config),
             allow_insecure: config.link_checker.allow_insecure,
+            max_concurrency: ClientConfig::max_concurrency(config),
+            max_concurrency_per_host: ClientConfig::max_concurrency_per_host(config),
+            retry_base_delay: Duration::from_millis(config.link_checker.rate_limit.base_delay),
+            retry_max_delay: Duration::from_millis(config.link_checker.rate_limit.max_delay),
+            min_delay_per_host: Duration::from_millis(
+                config.link_checker.rate_limit.min_delay_per_host,
+            ),
+            hosts: ClientConfig::hosts(config),
+            cookies: config
+                .link_checker
+                .cookie_jar
+                .as_ref()
+                .map(|path| cookies::load(path))
+                .unwrap_or_default(),
+            network: config.link_checker.network.clone(),
+        }
+    }
+
+    /// Builder of HTTP clients with timeout, user agent, proxy and TLS settings from the config
+    pub fn http_client_builder(&self) -> reqwest::ClientBuilder {
+        common::network::client_builder(&self.network)
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent)
+            .danger_accept_invalid_certs(self.allow_insecure)
+    }
+
+    /// Which host rule and cookies apply to the URL
+    pub fn headers_key(&self, url: &Url) -> HeadersKey {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        HeadersKey {
+            host: self.hosts.iter().position(|h| h.pattern.matches(&host)),
+            cookies: (0..self.cookies.len())
+                .filter(|i| self.cookies[*i].matches(url, now))
+                .collect(),
         }
     }
 
+    /// Headers for requests with the key. Values are marked as sensitive
+    pub fn headers(&self, key: &HeadersKey) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let host_headers = key.host.map(|i| &self.hosts[i].headers);
+        for (name, value) in host_headers.into_iter().flatten() {
+            if let Some(value) = to_header_value(value) {
+                headers.insert(name.clone(), value);
+            }
+        }
+        if !key.cookies.is_empty() {
+            let cookies = key
+                .cookies
+                .iter()
+                .map(|i| &self.cookies[*i])
+                .collect::<Vec<&Cookie>>();
+            if let Some(value) = to_header_value(&cookies::to_header(&cookies)) {
+                headers.insert(reqwest::header::COOKIE, value);
+            }
+        }
+        headers
+    }
+
+    /// Headers for the request to the URL
+    pub fn headers_for(&self, url: &Url) -> HeaderMap {
+        self.headers(&self.headers_key(url))
+    }
+
+    // Collect headers of hosts from config. Invalid headers and
+    // authentication with missing environment variables are skipped
+    fn hosts(config: &Config) -> Vec<HostHeaders> {
+        config
+            .link_checker
+            .hosts
+            .iter()
+            .map(|host| {
+                let mut headers: Vec<(HeaderName, SecretString)> = vec![];
+                for (name, value) in &host.headers {
+                    match HeaderName::from_bytes(name.as_bytes()) {
+                        Ok(name) => headers.push((name, SecretString::from(value.clone()))),
+                        Err(error) => log::warn!("Skipping invalid header {name:#?}: {error}"),
+                    }
+                }
+                if let Some(auth) = host.auth.as_ref().and_then(to_authorization) {
+                    headers.push((reqwest::header::AUTHORIZATION, auth));
+                }
+                HostHeaders {
+                    pattern: WildMatch::new(&host.host.to_lowercase()),
+                    headers,
+                }
+            })
+            .collect()
+    }
+
+    // Calculate maximum amount of concurrent requests from config
+    fn max_concurrency(config: &Config) -> usize {
+        config.link_checker.max_concurrency.unwrap_or(64).max(1) as usize
+    }
+
+    // Calculate maximum amount of concurrent requests to the same host from config
+    fn max_concurrency_per_host(config: &Config) -> usize {
+        config
+            .link_checker
+            .max_concurrency_per_host
+            .unwrap_or(8)
+            .max(1) as usize
+    }
+
     // Calculate accepted status codes from config
     fn accept_status_codes(config: &Config) -> HashSet<StatusCode> {
         HashSet::from_iter(
@@ -67,3 +198,35 @@ impl ClientConfig {
             .clone()
     }
 }
+
+/// Value of the "Authorization" header, None when environment variable with the secret is not set
+fn to_authorization(auth: &HostAuth) -> Option<SecretString> {
+    let read_env = |name: &str| match std::env::var(name) {
+        Ok(value) => Some(value),
+        Err(_) => {
+            log::warn!("Environment variable {name:#?} is not set, skipping authentication");
+            None
+        }
+    };
+    match auth {
+        HostAuth::Basic {
+            username,
+            password_env,
+        } => {
+            let password = read_env(password_env)?;
+            let credentials =
+                base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+            Some(SecretString::from(format!("Basic {credentials}")))
+        }
+        HostAuth::Bearer { token_env } => {
+            let token = read_env(token_env)?;
+            Some(SecretString::from(format!("Bearer {token}")))
+        }
+    }
+}
+
+fn to_header_value(value: &SecretString) -> Option<HeaderValue> {
+    let mut value = HeaderValue::from_str(value.expose_secret()).ok()?;
+    value.set_sensitive(true);
+    Some(value)
+}
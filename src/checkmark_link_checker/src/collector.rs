@@ -1,14 +1,23 @@
+use crate::locator::clean;
+use crate::root::{is_root_relative, RootResolver};
 use async_std::stream::StreamExt;
+use common::ast::{try_cast_to_html, BfsIterator};
 use common::Config;
 use log::debug;
-use lychee_lib::{Collector, Input, InputSource::*, Request, Result};
-use std::collections::HashMap;
+use lychee_lib::{Collector, Input, InputSource::*, Request, Result, Uri};
+use markdown::mdast::Node;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::string::String;
 use wildmatch::WildMatch;
 
-/// Collect links from file
-pub async fn collect(path: &str, config: &Config) -> Result<HashMap<String, Request>> {
+/// Collect links from file. Collector treats root-relative links as absolute
+/// paths on the filesystem, so they are resolved again with the resolver
+pub async fn collect(
+    path: &str,
+    config: &Config,
+    resolver: &RootResolver,
+) -> Result<HashMap<String, Request>> {
     let input = vec![Input {
         source: FsPath(PathBuf::from(path)),
         file_type_hint: Some(lychee_lib::FileType::Markdown),
@@ -26,8 +35,13 @@ pub async fn collect(path: &str, config: &Config) -> Result<HashMap<String, Requ
     debug!("Found links: {:#?}", &links);
 
     // Dedup them
+    let root_relative = find_root_relative_paths(path);
     let mut links_map: HashMap<String, Request> = HashMap::new();
-    for link in links {
+    for mut link in links {
+        if let Some(url) = rebase(&link.uri, &root_relative, resolver) {
+            debug!("Resolved root-relative {} to {url}", &link.uri);
+            link.uri = Uri { url };
+        }
         let uri = link.uri.as_str();
         let matches_any_ignored_uri_wildcard =
             config
@@ -49,3 +63,64 @@ pub async fn collect(path: &str, config: &Config) -> Result<HashMap<String, Requ
 
     Ok(links_map)
 }
+
+/// Paths of root-relative links in the file, both in Markdown and HTML
+fn find_root_relative_paths(path: &str) -> HashSet<PathBuf> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let ast = match common::ast::parse(&content) {
+        Ok(ast) => ast,
+        Err(_) => return HashSet::new(),
+    };
+    let mut links: Vec<String> = BfsIterator::from(&ast)
+        .filter_map(|node| match node {
+            Node::Link(l) => Some(l.url.clone()),
+            Node::Image(i) => Some(i.url.clone()),
+            Node::Definition(d) => Some(d.url.clone()),
+            _ => None,
+        })
+        .collect();
+    for html in BfsIterator::from(&ast).filter_map(|n| try_cast_to_html(n)) {
+        let fragment = scraper::Html::parse_fragment(&html.value);
+        for node in fragment.tree.nodes() {
+            if let Some(el) = node.value().as_element() {
+                links.extend(el.attr("href").map(String::from));
+                links.extend(el.attr("src").map(String::from));
+            }
+        }
+    }
+    links
+        .iter()
+        .filter(|link| is_root_relative(link))
+        .map(|link| {
+            let path = link.split(['#', '?']).next().unwrap_or_default();
+            clean(&PathBuf::from(
+                percent_encoding::percent_decode_str(path)
+                    .decode_utf8_lossy()
+                    .as_ref(),
+            ))
+        })
+        .collect()
+}
+
+/// URL of the file link that was written as root-relative, with its fragment kept
+fn rebase(
+    uri: &Uri,
+    root_relative: &HashSet<PathBuf>,
+    resolver: &RootResolver,
+) -> Option<reqwest::Url> {
+    if !uri.is_file() {
+        return None;
+    }
+    let mut url = uri.url.clone();
+    let fragment = url.fragment().map(String::from);
+    url.set_fragment(None);
+    let path = clean(&url.to_file_path().ok()?);
+    if !root_relative.contains(&path) {
+        return None;
+    }
+    let mut resolved = resolver.resolve(path.to_str()?)?;
+    if fragment.is_some() {
+        resolved.set_fragment(fragment.as_deref());
+    }
+    Some(resolved)
+}
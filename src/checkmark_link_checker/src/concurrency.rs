@@ -0,0 +1,70 @@
+use lychee_lib::Uri;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Limits number of concurrent requests to the same host
+/// and keeps minimum delay between them
+pub struct HostLimiter {
+    max_per_host: usize,
+    min_delay: Duration,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// When the next request to the host is allowed
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostLimiter {
+    pub fn new(max_per_host: usize, min_delay: Duration) -> Self {
+        Self {
+            max_per_host,
+            min_delay,
+            semaphores: Mutex::new(HashMap::new()),
+            next_request: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until request to the host of the URI is allowed.
+    /// Request is allowed while returned permit is alive.
+    /// URIs without host(files, e-mails) are not limited
+    pub async fn acquire(&self, uri: &Uri) -> Option<OwnedSemaphorePermit> {
+        let host = uri.url.host_str()?.to_lowercase();
+        let semaphore = self
+            .semaphores
+            .lock()
+            .unwrap()
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+            .clone();
+        let permit = semaphore.acquire_owned().await.ok();
+        self.wait(uri).await;
+        permit
+    }
+
+    /// Wait for the turn of the next request to the host of the URI
+    pub async fn wait(&self, uri: &Uri) {
+        let host = match uri.url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return,
+        };
+        let turn = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let now = Instant::now();
+            let turn = next_request.get(&host).copied().unwrap_or(now).max(now);
+            next_request.insert(host, turn + self.min_delay);
+            turn
+        };
+        tokio::time::sleep_until(turn).await;
+    }
+
+    /// Do not send requests to the host of the URI for a while, e.g. when it is rate-limited
+    pub fn pause(&self, uri: &Uri, delay: Duration) {
+        if let Some(host) = uri.url.host_str() {
+            let mut next_request = self.next_request.lock().unwrap();
+            let resume = Instant::now() + delay;
+            let turn = next_request.entry(host.to_lowercase()).or_insert(resume);
+            *turn = (*turn).max(resume);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use reqwest::Url;
+use secrecy::{ExposeSecret, SecretString};
+
+/// Single cookie from the cookie jar file
+pub struct Cookie {
+    domain: String,
+    include_subdomains: bool,
+    path: String,
+    secure: bool,
+    /// Unix timestamp, 0 for session cookies
+    expires: u64,
+    name: String,
+    value: SecretString,
+}
+
+impl Cookie {
+    /// Whether cookie shall be sent with the request to the URL
+    pub fn matches(&self, url: &Url, now: u64) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return false,
+        };
+        let is_domain_match = host == self.domain
+            || (self.include_subdomains && host.ends_with(&format!(".{}", self.domain)));
+        is_domain_match
+            && is_path_match(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && (self.expires == 0 || self.expires > now)
+    }
+}
+
+/// Path-match from RFC 6265: paths are equal, or cookie path is a prefix
+/// of the request path that ends with "/" or is followed by "/"
+fn is_path_match(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Read cookies from the file in Netscape format:
+/// "domain, include subdomains, path, secure, expires, name, value" separated by tabs.
+/// Malformed lines are skipped, unreadable file results in no cookies
+pub fn load(path: &str) -> Vec<Cookie> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse(&content),
+        Err(error) => {
+            log::warn!("Unable to read cookie jar {path:#?}: {error}");
+            vec![]
+        }
+    }
+}
+
+fn parse(content: &str) -> Vec<Cookie> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // Cookies with HttpOnly flag are prefixed with "#HttpOnly_"
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 7 {
+                return None;
+            }
+            Some(Cookie {
+                domain: fields[0].trim_start_matches('.').to_lowercase(),
+                include_subdomains: fields[1].eq_ignore_ascii_case("TRUE"),
+                path: fields[2].to_string(),
+                secure: fields[3].eq_ignore_ascii_case("TRUE"),
+                expires: fields[4].parse().ok()?,
+                name: fields[5].to_string(),
+                value: SecretString::from(fields[6].to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Value of the "Cookie" header with the given cookies
+pub fn to_header(cookies: &[&Cookie]) -> SecretString {
+    SecretString::from(
+        cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value.expose_secret()))
+            .collect::<Vec<String>>()
+            .join("; "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookies_matched_by_domain_path_and_scheme() {
+        let cookies = parse(
+            "# Netscape HTTP Cookie File\n\
+             .example.com\tTRUE\t/\tFALSE\t0\tsession\tabc\n\
+             #HttpOnly_wiki.example.com\tFALSE\t/private\tTRUE\t0\ttoken\txyz\n\
+             old.example.com\tFALSE\t/\tFALSE\t1\texpired\t1\n",
+        );
+        assert_eq!(3, cookies.len());
+        let url = Url::parse("https://wiki.example.com/private/page").unwrap();
+        let matching = cookies
+            .iter()
+            .filter(|cookie| cookie.matches(&url, 100))
+            .collect::<Vec<&Cookie>>();
+        assert_eq!(
+            "session=abc; token=xyz",
+            to_header(&matching).expose_secret()
+        );
+        let insecure = Url::parse("http://wiki.example.com/private/page").unwrap();
+        assert_eq!(
+            1,
+            cookies.iter().filter(|c| c.matches(&insecure, 100)).count()
+        );
+        let sibling = Url::parse("https://wiki.example.com/privatefoo").unwrap();
+        assert!(!cookies[1].matches(&sibling, 100));
+        let exact = Url::parse("https://wiki.example.com/private").unwrap();
+        assert!(cookies[1].matches(&exact, 100));
+        let old = Url::parse("https://old.example.com").unwrap();
+        assert!(!cookies[2].matches(&old, 100));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::client_config::ClientConfig;
+use lychee_lib::{ErrorKind, Status, Uri};
+
+/// Client for requests that need headers of the response, which lychee does not expose,
+/// e.g. "Retry-After" of rate-limited links. Redirects are handled like lychee client does
+pub fn build_client(client_config: &ClientConfig, follows_redirects: bool) -> reqwest::Client {
+    client_config
+        .http_client_builder()
+        .redirect(crate::redirect::policy(follows_redirects))
+        .build()
+        .unwrap()
+}
+
+/// Send GET request with headers of the host. Status of the failure when request is not sent
+pub async fn send(
+    client: &reqwest::Client,
+    client_config: &ClientConfig,
+    uri: &Uri,
+) -> Result<reqwest::Response, Status> {
+    let request = client
+        .get(uri.url.clone())
+        .headers(client_config.headers_for(&uri.url));
+    match request.send().await {
+        Ok(response) => Ok(response),
+        Err(error) if error.is_timeout() => Err(Status::Timeout(error.status())),
+        Err(error) => Err(Status::Error(ErrorKind::NetworkRequest(error))),
+    }
+}
+
+/// Successful and accepted responses are fine, redirects that are not followed
+/// are reported as such and the rest are errors
+pub fn to_status(response: reqwest::Response, client_config: &ClientConfig) -> Status {
+    let status = response.status();
+    if status.is_success() || client_config.accepted_status_codes.contains(&status) {
+        return Status::Ok(status);
+    }
+    if status.is_redirection() {
+        return Status::Redirected(status);
+    }
+    match response.error_for_status() {
+        Err(error) => Status::Error(ErrorKind::NetworkRequest(error)),
+        Ok(_) => Status::Ok(status),
+    }
+}
@@ -0,0 +1,194 @@
+use common::{EmailCheckMode, EmailsConfig};
+use email_address::EmailAddress;
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use log::{debug, warn};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use wildmatch::WildMatch;
+
+/// Domains reserved for documentation and testing(RFC 2606, RFC 6761)
+const PLACEHOLDER_DOMAINS: [&str; 4] = ["example.com", "example.org", "example.net", "localhost"];
+const PLACEHOLDER_TLDS: [&str; 4] = ["example", "test", "invalid", "localhost"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmailStatus {
+    Valid,
+    /// Address is not valid according to RFC 5322
+    InvalidSyntax,
+    /// Domain is reserved for documentation and testing, e.g. "user@example.com"
+    Placeholder,
+    /// Domain is in the deny list
+    Denied,
+    /// Domain has neither MX records nor an address to deliver mail to
+    NoMailServer,
+    /// Mail server of the domain could not be looked up
+    LookupFailed(String),
+    /// Address looks fine, mail server shall be asked whether it exists
+    Unverified,
+}
+
+/// Validates e-mail addresses according to the mode from the config
+pub struct EmailValidator {
+    mode: EmailCheckMode,
+    allowed: Vec<WildMatch>,
+    denied: Vec<WildMatch>,
+    resolver: Option<TokioAsyncResolver>,
+}
+
+impl EmailValidator {
+    pub fn new(config: &EmailsConfig) -> Self {
+        let wildcards = |patterns: &Vec<String>| {
+            patterns
+                .iter()
+                .map(|pattern| WildMatch::new(&pattern.to_lowercase()))
+                .collect()
+        };
+        Self {
+            mode: config.mode.clone(),
+            allowed: wildcards(&config.allow_domains),
+            denied: wildcards(&config.deny_domains),
+            resolver: match config.mode {
+                EmailCheckMode::Mx => Some(build_resolver(config.resolver.as_deref())),
+                _ => None,
+            },
+        }
+    }
+
+    /// Validate "mailto:" URI without network access, regardless of the mode
+    pub fn validate_offline(&self, uri: &str) -> EmailStatus {
+        match self.precheck(uri) {
+            Ok(_) => EmailStatus::Valid,
+            Err(status) => status,
+        }
+    }
+
+    /// Validate "mailto:" URI. Unverified addresses are left for the SMTP check
+    pub async fn validate(&self, uri: &str) -> EmailStatus {
+        let domain = match self.precheck(uri) {
+            Ok(domain) => domain,
+            Err(status) => return status,
+        };
+        match self.mode {
+            EmailCheckMode::Smtp => EmailStatus::Unverified,
+            EmailCheckMode::Mx => self.lookup_mail_server(&domain).await,
+            EmailCheckMode::Syntax => EmailStatus::Valid,
+        }
+    }
+
+    /// Domain of the address that needs further checks,
+    /// or the final status when address is invalid or its domain is listed
+    fn precheck(&self, uri: &str) -> Result<String, EmailStatus> {
+        let address = uri.strip_prefix("mailto:").unwrap_or(uri);
+        let address = address
+            .split_once('?')
+            .map_or(address, |(address, _)| address);
+        let address = percent_encoding::percent_decode_str(address).decode_utf8_lossy();
+        let email = EmailAddress::from_str(&address).map_err(|_| EmailStatus::InvalidSyntax)?;
+        let domain = email.domain().to_lowercase();
+        if self.allowed.iter().any(|pattern| pattern.matches(&domain)) {
+            return Err(EmailStatus::Valid);
+        }
+        if self.denied.iter().any(|pattern| pattern.matches(&domain)) {
+            return Err(EmailStatus::Denied);
+        }
+        if is_placeholder(&domain) {
+            return Err(EmailStatus::Placeholder);
+        }
+        Ok(domain)
+    }
+
+    /// Domain without MX records receives mail on its own address(RFC 5321),
+    /// while a single "." MX record explicitly says it receives no mail(RFC 7505)
+    async fn lookup_mail_server(&self, domain: &str) -> EmailStatus {
+        let resolver = match &self.resolver {
+            Some(resolver) => resolver,
+            None => return EmailStatus::Valid,
+        };
+        // Trailing dot prevents search domains from being appended
+        let name = format!("{domain}.");
+        debug!("Looking up mail server of {domain}");
+        let error = match resolver.mx_lookup(name.as_str()).await {
+            Ok(lookup) => {
+                return match lookup.iter().all(|mx| mx.exchange().is_root()) {
+                    true => EmailStatus::NoMailServer,
+                    false => EmailStatus::Valid,
+                }
+            }
+            Err(error) => error,
+        };
+        if !matches!(error.kind(), ResolveErrorKind::NoRecordsFound { .. }) {
+            return EmailStatus::LookupFailed(error.to_string());
+        }
+        match resolver.lookup_ip(name.as_str()).await {
+            Ok(_) => EmailStatus::Valid,
+            Err(error) => match error.kind() {
+                ResolveErrorKind::NoRecordsFound { .. } => EmailStatus::NoMailServer,
+                _ => EmailStatus::LookupFailed(error.to_string()),
+            },
+        }
+    }
+}
+
+fn is_placeholder(domain: &str) -> bool {
+    let tld = domain.rsplit('.').next().unwrap_or_default();
+    PLACEHOLDER_DOMAINS
+        .iter()
+        .any(|placeholder| domain == *placeholder || domain.ends_with(&format!(".{placeholder}")))
+        || PLACEHOLDER_TLDS.contains(&tld)
+}
+
+/// Resolver that uses the DNS server from the config, e.g. "1.1.1.1" or "1.1.1.1:53",
+/// or the system one when it is not set
+fn build_resolver(address: Option<&str>) -> TokioAsyncResolver {
+    if let Some(address) = address {
+        let server = address.parse::<SocketAddr>().ok().or_else(|| {
+            address
+                .parse::<IpAddr>()
+                .ok()
+                .map(|ip| SocketAddr::new(ip, 53))
+        });
+        match server {
+            Some(server) => {
+                let servers =
+                    NameServerConfigGroup::from_ips_clear(&[server.ip()], server.port(), true);
+                let config = ResolverConfig::from_parts(None, vec![], servers);
+                return TokioAsyncResolver::tokio(config, ResolverOpts::default());
+            }
+            None => warn!("Ignoring invalid DNS resolver {address:#?}, using the system one"),
+        }
+    }
+    TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|error| {
+        warn!("Unable to read system DNS configuration, using default resolver: {error}");
+        TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_validated_without_network() {
+        let validator = EmailValidator::new(&EmailsConfig {
+            mode: EmailCheckMode::Syntax,
+            resolver: None,
+            allow_domains: vec![String::from("*.corp.test")],
+            deny_domains: vec![String::from("*mailinator.com")],
+        });
+        for (uri, expected) in [
+            ("mailto:jane@company.io", EmailStatus::Valid),
+            ("mailto:jane@company.io?subject=Hi", EmailStatus::Valid),
+            ("mailto:jane.company.io", EmailStatus::InvalidSyntax),
+            ("mailto:user@example.com", EmailStatus::Placeholder),
+            ("mailto:user@docs.example.org", EmailStatus::Placeholder),
+            ("mailto:foo@localhost", EmailStatus::Placeholder),
+            ("mailto:foo@site.test", EmailStatus::Placeholder),
+            ("mailto:ci@build.corp.test", EmailStatus::Valid),
+            ("mailto:spam@mailinator.com", EmailStatus::Denied),
+        ] {
+            assert_eq!(expected, validator.validate_offline(uri), "{uri}");
+        }
+    }
+}
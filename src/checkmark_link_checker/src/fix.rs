@@ -0,0 +1,51 @@
+use markdown::unist::Position;
+
+/// Replacement of the link's URL in the source
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkFix {
+    /// Position of the link, URL is replaced only within it
+    pub position: Position,
+    /// URL as it is written in the source
+    pub from: String,
+    pub to: String,
+}
+
+/// Apply fixes to the source. Fixes which URL is not found at their position are skipped
+pub fn apply_fixes(source: &str, fixes: &[LinkFix]) -> String {
+    let mut fixes = fixes.to_vec();
+    // Apply from the end, so offsets of fixes before remain valid
+    fixes.sort_by_key(|fix| std::cmp::Reverse(fix.position.start.offset));
+    let mut result = source.to_string();
+    for fix in fixes {
+        let span = fix.position.start.offset..fix.position.end.offset;
+        // URL goes after the text of a link or a label of a definition
+        if let Some(index) = result.get(span.clone()).and_then(|s| s.rfind(&fix.from)) {
+            let start = span.start + index;
+            result.replace_range(start..start + fix.from.len(), &fix.to);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown::unist::Point;
+
+    #[test]
+    fn url_replaced_within_link() {
+        let source = "[http://a.com](http://a.com) and http://a.com";
+        let fix = LinkFix {
+            position: Position {
+                start: Point::new(1, 1, 0),
+                end: Point::new(1, 29, 28),
+            },
+            from: String::from("http://a.com"),
+            to: String::from("https://b.com"),
+        };
+        assert_eq!(
+            "[http://a.com](https://b.com) and http://a.com",
+            apply_fixes(source, &[fix])
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use super::utils::issue_at;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    status_code: &Option<u16>,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling cached error");
+    let mut issues: Vec<CheckIssue> = vec![];
+    let reason = match status_code {
+        Some(code) => format!("with status code {code}"),
+        None => String::from("with an error"),
+    };
+    for position in positions {
+        let mut issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Warning)
+            .set_message(format!(
+                "Request to {uri} failed {reason} (cached result of a previous run)"
+            ));
+        issue = issue.push_fix(&format!(
+            "🧠 {}  {}",
+            "Rationale".cyan(),
+            "Having a broken hyperlink is a bad, confusing user experience"
+        ));
+        let fixes = vec![
+            format!("Can you open this link in a browser? If no then perhaps its broken and shall be fixed"),
+            format!("If the link has been fixed already, run link checker with --refresh to check it again"),
+        ];
+        for fix in fixes {
+            issue = issue.push_fix(&format!("💡 {} {}", "Suggestion".cyan(), fix));
+        }
+        issues.push(issue.build());
+    }
+    issues
+}
@@ -0,0 +1,40 @@
+use super::utils::issue_at;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+use std::path::Path;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    actual: &Path,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling case mismatch with {actual:#?}");
+    let linked_name = Path::new(uri)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let actual_name = actual.file_name().unwrap_or_default().to_string_lossy();
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Warning)
+            .set_message(format!(
+                "Case of \"{uri}\" does not match the file \"{}\"",
+                actual.display()
+            ))
+            .push_fix(&format!(
+                "🧠 {}  {}",
+                "Rationale".cyan(),
+                "File systems of macOS and Windows ignore case, so the link works there, but it is broken on Linux and on most web servers"
+            ))
+            .push_fix(&format!(
+                "💡 {} Rename \"{linked_name}\" to \"{actual_name}\" in the link",
+                "Suggestion".cyan()
+            ));
+        issues.push(issue.build());
+    }
+    issues
+}
@@ -0,0 +1,58 @@
+use super::utils::issue_at;
+use crate::email::EmailStatus;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    status: &EmailStatus,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling invalid e-mail: {status:?}");
+    let address = uri.strip_prefix("mailto:").unwrap_or(uri);
+    let (severity, message, suggestion) = match status {
+        EmailStatus::InvalidSyntax => (
+            IssueSeverity::Warning,
+            format!("E-mail \"{address}\" is not a valid address"),
+            String::from("Check the address for typos, e.g. a missing \"@\" or an extra space"),
+        ),
+        EmailStatus::Placeholder => (
+            IssueSeverity::Warning,
+            format!("E-mail \"{address}\" is a placeholder"),
+            String::from("Replace it with a real address, or format it as code when it is just an example"),
+        ),
+        EmailStatus::Denied => (
+            IssueSeverity::Warning,
+            format!("Domain of the e-mail \"{address}\" is not allowed"),
+            String::from("Use an address in another domain, or remove the domain from \"deny_domains\" in the [link_checker.emails] section of the config"),
+        ),
+        EmailStatus::NoMailServer => (
+            IssueSeverity::Warning,
+            format!("Domain of the e-mail \"{address}\" has no mail server"),
+            String::from("Is the domain spelled correctly? If it is an internal domain, add it to \"allow_domains\" in the [link_checker.emails] section of the config"),
+        ),
+        EmailStatus::LookupFailed(error) => (
+            IssueSeverity::Note,
+            format!("Unable to look up the mail server of the e-mail \"{address}\". Reason: {error}"),
+            String::from("Check the network connection or set \"resolver\" in the [link_checker.emails] section of the config"),
+        ),
+        EmailStatus::Valid | EmailStatus::Unverified => return vec![],
+    };
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let issue = issue_at(file, position)
+            .set_severity(severity.clone())
+            .set_message(message.clone())
+            .push_fix(&format!(
+                "🧠 {}  {}",
+                "Rationale".cyan(),
+                "Broken e-mails make it hard for people to contact you"
+            ))
+            .push_fix(&format!("💡 {} {}", "Suggestion".cyan(), suggestion));
+        issues.push(issue.build());
+    }
+    issues
+}
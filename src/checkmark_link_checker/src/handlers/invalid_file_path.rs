@@ -1,24 +1,17 @@
-use super::utils::find_all_links_in_file;
+use super::utils::issue_at;
 use colored::Colorize;
-use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity, MarkDownFile};
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
 use log::debug;
+use markdown::unist::Position;
 use std::path::Path;
 
-pub fn handle(file: &MarkDownFile, uri: &str) -> Vec<CheckIssue> {
+pub fn handle(file: &MarkDownFile, uri: &str, positions: &[Position]) -> Vec<CheckIssue> {
     debug!("{:#?} - handling invalid file path error", &uri);
     let mut issues: Vec<CheckIssue> = vec![];
     let broken_filename = Path::new(&uri).file_name().unwrap().to_str().unwrap();
-    for offset in find_all_links_in_file(file, broken_filename) {
-        let mut issue = CheckIssueBuilder::default()
-            .set_category(IssueCategory::LinkChecking)
+    for position in positions {
+        let mut issue = issue_at(file, position)
             .set_severity(IssueSeverity::Warning)
-            .set_file_path(file.path.clone())
-            .set_row_num_start(1)
-            .set_row_num_end(file.content.lines().count())
-            .set_col_num_start(1)
-            .set_col_num_end(1)
-            .set_offset_start(offset.start)
-            .set_offset_end(offset.end)
             .set_message(format!(
                 "File \"{broken_filename}\" is not found in path \"{uri}\"",
             ));
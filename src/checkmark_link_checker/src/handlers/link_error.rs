@@ -0,0 +1,253 @@
+use super::utils::issue_at;
+use colored::Colorize;
+use common::{CheckIssue, Config, IssueSeverity, LinkErrorSeverity, MarkDownFile};
+use log::debug;
+use lychee_lib::ErrorKind;
+use markdown::unist::Position;
+use reqwest::StatusCode;
+
+/// Error of a link that is reported as an issue
+#[derive(Debug, PartialEq)]
+pub struct LinkError {
+    /// Name of the kind, used to configure severity
+    pub kind: &'static str,
+    /// Severity used when it is not configured
+    pub severity: IssueSeverity,
+    pub message: String,
+    pub rationale: &'static str,
+    pub suggestions: Vec<String>,
+}
+
+/// Describe error of the link. Returns None for errors that are
+/// not related to the link itself, e.g. failure to read the input
+pub fn describe(uri: &str, error: &ErrorKind) -> Option<LinkError> {
+    let error = match error {
+        ErrorKind::TooManyRedirects(error) => LinkError {
+            kind: "too_many_redirects",
+            severity: IssueSeverity::Warning,
+            message: format!("Request to {uri} was redirected too many times: {error}"),
+            rationale: "Endless redirects usually mean that the page has moved or the server is misconfigured",
+            suggestions: vec![
+                String::from("Open this link in a browser and replace it with the URL you end up at"),
+            ],
+        },
+        ErrorKind::InsecureURL(secure) => LinkError {
+            kind: "insecure_url",
+            severity: IssueSeverity::Warning,
+            message: format!("Link {uri} uses an insecure http:// URL while https:// variant is available"),
+            rationale: "Insecure links expose readers to eavesdropping and tampering",
+            suggestions: vec![format!("Use the https:// variant: {secure}")],
+        },
+        ErrorKind::InvalidGithubUrl(url) => LinkError {
+            kind: "invalid_github_url",
+            severity: IssueSeverity::Warning,
+            message: format!("Link {url} is not a valid GitHub URL"),
+            rationale: "Having a broken hyperlink is a bad, confusing user experience",
+            suggestions: vec![String::from(
+                "Check owner and repository name in the URL, it shall look like https://github.com/<owner>/<repo>",
+            )],
+        },
+        ErrorKind::MissingGitHubToken => LinkError {
+            kind: "missing_github_token",
+            severity: IssueSeverity::Note,
+            message: format!("Link {uri} was not verified, GitHub requires a token to check it"),
+            rationale: "GitHub rate-limits and rejects anonymous requests",
+            suggestions: vec![String::from(
+                "Set --github-token CLI option or github_token in the [link_checker] section of the config",
+            )],
+        },
+        ErrorKind::GithubRequest(error) => LinkError {
+            kind: "github_request",
+            severity: IssueSeverity::Warning,
+            message: format!("GitHub API request for {uri} failed: {error}"),
+            rationale: "Having a broken hyperlink is a bad, confusing user experience",
+            suggestions: vec![
+                String::from("Does this repository exist and is it public?"),
+                String::from("If you are rate-limited, set --github-token CLI option"),
+            ],
+        },
+        ErrorKind::ParseUrl(error, url) => LinkError {
+            kind: "parse_url",
+            severity: IssueSeverity::Error,
+            message: format!("Link {url} is not a valid URL: {error}"),
+            rationale: "Invalid URL can not be opened by any reader",
+            suggestions: vec![String::from(
+                "Check the URL for typos, spaces and a missing scheme(e.g. https://)",
+            )],
+        },
+        ErrorKind::EmptyUrl => LinkError {
+            kind: "empty_url",
+            severity: IssueSeverity::Error,
+            message: String::from("Link has an empty URL"),
+            rationale: "Link without a destination leads nowhere",
+            suggestions: vec![String::from("Add a destination to the link or remove the link")],
+        },
+        ErrorKind::InvalidUrlHost => LinkError {
+            kind: "invalid_url_host",
+            severity: IssueSeverity::Error,
+            message: format!("Link {uri} has an invalid host"),
+            rationale: "Invalid URL can not be opened by any reader",
+            suggestions: vec![String::from("Check the host name of the URL for typos")],
+        },
+        ErrorKind::InvalidURI(invalid) => LinkError {
+            kind: "invalid_uri",
+            severity: IssueSeverity::Error,
+            message: format!("Link {invalid} is not a valid URI"),
+            rationale: "Invalid URL can not be opened by any reader",
+            suggestions: vec![String::from("Check the URL for typos and unescaped characters")],
+        },
+        ErrorKind::InvalidStatusCode(code) => LinkError {
+            kind: "invalid_status_code",
+            severity: IssueSeverity::Warning,
+            message: format!("Server responded to {uri} with an invalid status code {code}"),
+            rationale: "Server that responds with invalid status codes may be broken",
+            suggestions: vec![String::from(
+                "Can you open this link in a browser? If no then perhaps its broken and shall be fixed",
+            )],
+        },
+        ErrorKind::InvalidHeader(error) => LinkError {
+            kind: "invalid_header",
+            severity: IssueSeverity::Warning,
+            message: format!("Request to {uri} was not sent, invalid HTTP header: {error}"),
+            rationale: "Link can not be verified without a valid request",
+            suggestions: vec![String::from("Check HTTP headers in the config")],
+        },
+        _ => return None,
+    };
+    Some(error)
+}
+
+/// Describe response with a status code that is not known
+pub fn describe_unknown_status_code(uri: &str, code: &StatusCode) -> LinkError {
+    LinkError {
+        kind: "unknown_status_code",
+        severity: IssueSeverity::Warning,
+        message: format!("Server responded to {uri} with an unknown status code {code}"),
+        rationale: "Non-standard status codes may indicate that the page is broken",
+        suggestions: vec![
+            String::from("Can you open this link in a browser? If no then perhaps its broken and shall be fixed"),
+            format!("If the link is fine, add {} to the accept list in the config", code.as_u16()),
+        ],
+    }
+}
+
+/// Severity of the error according to the config, None when it shall not be reported
+pub fn severity(error: &LinkError, config: &Config) -> Option<IssueSeverity> {
+    match config.link_checker.error_severity.get(error.kind) {
+        Some(LinkErrorSeverity::Error) => Some(IssueSeverity::Error),
+        Some(LinkErrorSeverity::Warning) => Some(IssueSeverity::Warning),
+        Some(LinkErrorSeverity::Note) => Some(IssueSeverity::Note),
+        Some(LinkErrorSeverity::Ignore) => None,
+        None => Some(error.severity.clone()),
+    }
+}
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    error: &LinkError,
+    config: &Config,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling {} error", error.kind);
+    let severity = match severity(error, config) {
+        Some(severity) => severity,
+        None => {
+            debug!("{uri} - {} errors are ignored", error.kind);
+            return vec![];
+        }
+    };
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let mut issue = issue_at(file, position)
+            .set_severity(severity.clone())
+            .set_message(error.message.clone());
+        issue = issue.push_fix(&format!("🧠 {}  {}", "Rationale".cyan(), error.rationale));
+        for fix in &error.suggestions {
+            issue = issue.push_fix(&format!("💡 {} {}", "Suggestion".cyan(), fix));
+        }
+        issues.push(issue.build());
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lychee_lib::Uri;
+
+    #[test]
+    fn insecure_url_suggests_https() {
+        let secure = Uri::try_from("https://example.com/").unwrap();
+        let error = describe("http://example.com", &ErrorKind::InsecureURL(secure)).unwrap();
+        assert_eq!("insecure_url", error.kind);
+        assert_eq!(IssueSeverity::Warning, error.severity);
+        assert_eq!(
+            vec![String::from(
+                "Use the https:// variant: https://example.com/"
+            )],
+            error.suggestions
+        );
+    }
+
+    #[test]
+    fn invalid_urls_are_errors() {
+        let errors = [
+            ErrorKind::ParseUrl(url::ParseError::EmptyHost, String::from("https://")),
+            ErrorKind::EmptyUrl,
+            ErrorKind::InvalidUrlHost,
+        ];
+        let kinds = errors
+            .iter()
+            .map(|e| describe("https://", e).unwrap())
+            .inspect(|e| assert_eq!(IssueSeverity::Error, e.severity))
+            .map(|e| e.kind)
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["parse_url", "empty_url", "invalid_url_host"], kinds);
+    }
+
+    #[test]
+    fn github_errors_are_described() {
+        let invalid = describe(
+            "https://github.com/x",
+            &ErrorKind::InvalidGithubUrl(String::from("https://github.com/x")),
+        )
+        .unwrap();
+        assert_eq!("invalid_github_url", invalid.kind);
+        let missing = describe("https://github.com/x/y", &ErrorKind::MissingGitHubToken).unwrap();
+        assert_eq!("missing_github_token", missing.kind);
+        assert_eq!(IssueSeverity::Note, missing.severity);
+        assert!(missing.suggestions[0].contains("--github-token"));
+    }
+
+    #[test]
+    fn unknown_status_code_is_described() {
+        let code = StatusCode::from_u16(599).unwrap();
+        let error = describe_unknown_status_code("https://example.com", &code);
+        assert_eq!("unknown_status_code", error.kind);
+        assert!(error.message.contains("599"));
+    }
+
+    #[test]
+    fn unrelated_errors_are_not_described() {
+        let error = ErrorKind::InvalidBase(String::from("base"), String::from("error"));
+        assert_eq!(None, describe("https://example.com", &error));
+    }
+
+    #[test]
+    fn severity_is_configurable() {
+        let error = describe("https://example.com", &ErrorKind::InvalidUrlHost).unwrap();
+        let mut config = Config::default();
+        assert_eq!(Some(IssueSeverity::Error), severity(&error, &config));
+        config
+            .link_checker
+            .error_severity
+            .insert(String::from("invalid_url_host"), LinkErrorSeverity::Note);
+        assert_eq!(Some(IssueSeverity::Note), severity(&error, &config));
+        config
+            .link_checker
+            .error_severity
+            .insert(String::from("invalid_url_host"), LinkErrorSeverity::Ignore);
+        assert_eq!(None, severity(&error, &config));
+    }
+}
@@ -0,0 +1,39 @@
+use super::utils::issue_at;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+use std::path::Path;
+
+pub fn handle(
+    file: &MarkDownFile,
+    path: &Path,
+    positions: &[Position],
+    fragment: &str,
+) -> Vec<CheckIssue> {
+    debug!("{path:#?} - handling missing fragment {fragment:#?}");
+    let mut issues: Vec<CheckIssue> = vec![];
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    for position in positions {
+        let mut issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Warning)
+            .set_message(format!(
+                "Fragment \"#{fragment}\" is not found in file \"{}\"",
+                path.display()
+            ));
+        issue = issue.push_fix(&format!(
+            "🧠 {}  {}",
+            "Rationale".cyan(),
+            "Link to a missing section opens the top of the document instead of the expected one"
+        ));
+        let fixes = vec![
+            format!("Was the heading renamed or removed? Fragment shall match the generated name of a heading in {filename:#?}"),
+            format!("Alternatively, add an HTML element with id=\"{fragment}\" to {filename:#?}"),
+        ];
+        for fix in fixes {
+            issue = issue.push_fix(&format!("💡 {} {}", "Suggestion".cyan(), fix));
+        }
+        issues.push(issue.build());
+    }
+    issues
+}
@@ -0,0 +1,37 @@
+use super::utils::issue_at;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    fragment: &str,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling missing remote fragment {fragment:#?}");
+    let mut issues: Vec<CheckIssue> = vec![];
+    let page = uri.split_once('#').map(|(page, _)| page).unwrap_or(uri);
+    for position in positions {
+        let mut issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Warning)
+            .set_message(format!(
+                "Fragment \"#{fragment}\" is not found on page {page}"
+            ));
+        issue = issue.push_fix(&format!(
+            "🧠 {}  {}",
+            "Rationale".cyan(),
+            "Link to a missing section opens the top of the page instead of the expected one"
+        ));
+        let fixes = vec![
+            format!("Was the section renamed or removed? Open {page} and copy the link to the section again"),
+            format!("If the page renders its content with JavaScript, the fragment can't be verified. Consider adding this URL to ignore_wildcards"),
+        ];
+        for fix in fixes {
+            issue = issue.push_fix(&format!("💡 {} {}", "Suggestion".cyan(), fix));
+        }
+        issues.push(issue.build());
+    }
+    issues
+}
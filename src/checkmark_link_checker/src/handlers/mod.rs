@@ -1,19 +1,117 @@
+mod cached_error;
+mod case_mismatch;
+mod invalid_email;
 mod invalid_file_path;
+mod link_error;
+mod missing_fragment;
+mod missing_remote_fragment;
 mod network_request_error;
+mod permanent_redirect;
+mod rate_limited;
 mod request_timeout;
+mod skipped_url;
 mod unreachable_email;
+mod unused_asset;
 mod utils;
 
 use crate::client_config::ClientConfig;
-use common::{CheckIssue, MarkDownFile};
+use crate::email::EmailStatus;
+use crate::offline::OfflineStatus;
+use crate::redirect::RedirectChain;
+use common::{CheckIssue, Config, MarkDownFile};
 use log::debug;
-use lychee_lib::{ErrorKind, Response, Status};
+use lychee_lib::{CacheStatus, ErrorKind, Response, Status};
+use markdown::unist::Position;
+use std::path::Path;
+
+pub fn handle_offline_status(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    status: &OfflineStatus,
+    config: &Config,
+) -> Vec<CheckIssue> {
+    debug!("Handling offline status for {uri}");
+    match status {
+        OfflineStatus::Ok => vec![],
+        OfflineStatus::Skipped if config.link_checker.list_skipped => {
+            skipped_url::handle(file, uri, positions)
+        }
+        OfflineStatus::Skipped => vec![],
+        OfflineStatus::MissingPath(path) => {
+            invalid_file_path::handle(file, &path.to_string_lossy(), positions)
+        }
+        OfflineStatus::MissingFragment(path, fragment) => {
+            missing_fragment::handle(file, path, positions, fragment)
+        }
+        OfflineStatus::InvalidEmail(status) => invalid_email::handle(file, uri, positions, status),
+    }
+}
+
+pub fn handle_redirect(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    chain: &RedirectChain,
+    config: &Config,
+) -> Vec<CheckIssue> {
+    let redirects = &config.link_checker.redirects;
+    match redirects.report && chain.is_reportable(redirects) {
+        true => permanent_redirect::handle(file, uri, positions, chain),
+        false => {
+            debug!("{uri} - redirect is not reported: {}", chain.describe());
+            vec![]
+        }
+    }
+}
+
+pub fn handle_missing_remote_fragment(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    fragment: &str,
+) -> Vec<CheckIssue> {
+    missing_remote_fragment::handle(file, uri, positions, fragment)
+}
+
+pub fn handle_case_mismatch(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    actual: &Path,
+) -> Vec<CheckIssue> {
+    case_mismatch::handle(file, uri, positions, actual)
+}
+
+pub fn handle_invalid_email(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    status: &EmailStatus,
+) -> Vec<CheckIssue> {
+    invalid_email::handle(file, uri, positions, status)
+}
+
+pub fn handle_unused_asset(path: &Path) -> CheckIssue {
+    unused_asset::handle(path)
+}
+
+pub fn handle_rate_limited(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    client_config: &ClientConfig,
+) -> Vec<CheckIssue> {
+    rate_limited::handle(file, uri, positions, client_config.max_retries)
+}
 
 pub fn handle_response(
     file: &MarkDownFile,
     uri: &String,
+    positions: &[Position],
     response: &Response,
     client_config: &ClientConfig,
+    config: &Config,
 ) -> Vec<CheckIssue> {
     debug!("Handling response for {uri}");
     match response.status() {
@@ -26,7 +124,9 @@ pub fn handle_response(
         Status::Error(error_kind) => {
             match error_kind {
                 // Network error while handling request
-                ErrorKind::NetworkRequest(error) => network_request_error::handle(file, uri, error),
+                ErrorKind::NetworkRequest(error) => {
+                    network_request_error::handle(file, uri, positions, error)
+                }
                 // Cannot read the body of the received response
                 ErrorKind::ReadResponseBody(error) => {
                     debug!("{uri} respond OK, but unable to read response body, error: {error}");
@@ -37,14 +137,6 @@ pub fn handle_response(
                     debug!("{uri} request cant build client, error: {error}");
                     vec![]
                 }
-                // Network error while using Github API
-                ErrorKind::GithubRequest(error) => {
-                    debug!(
-                        "{:#?} request error while using GitHub API, error:\n{:#?}",
-                        &uri, &error
-                    );
-                    vec![]
-                }
                 // Error while executing a future on the Tokio runtime
                 ErrorKind::RuntimeJoin(error) => {
                     debug!("{:#?} request error while executing a future on the Tokio runtime, error:\n{:#?}", &uri, &error);
@@ -79,29 +171,8 @@ pub fn handle_response(
                     );
                     vec![]
                 }
-                // Invalid Github URL
-                ErrorKind::InvalidGithubUrl(invalid_github_url) => {
-                    debug!(
-                        "{:#?} request error invalid GitHub URL: {:#?}",
-                        &uri, &invalid_github_url
-                    );
-                    vec![]
-                }
-                // The input is empty and not accepted as a valid URL
-                ErrorKind::EmptyUrl => {
-                    debug!("{:#?} request error empty URL", &uri);
-                    vec![]
-                }
-                // The given string can not be parsed into a valid URL, e-mail address, or file path
-                ErrorKind::ParseUrl(error, url) => {
-                    debug!(
-                        "{:#?} request error cant parse URL, error:\n{:#?}",
-                        &url, &error
-                    );
-                    vec![]
-                }
                 // The given URI cannot be converted to a file path
-                ErrorKind::InvalidFilePath(_) => invalid_file_path::handle(file, uri),
+                ErrorKind::InvalidFilePath(_) => invalid_file_path::handle(file, uri, positions),
                 // The given path cannot be converted to a URI
                 ErrorKind::InvalidUrlFromPath(uri_path) => {
                     debug!("{:#?} request error given path cannot be converted to a URI, uri_path: {:#?}", &uri, &uri_path);
@@ -109,12 +180,7 @@ pub fn handle_response(
                 }
                 // The given mail address is unreachable
                 ErrorKind::UnreachableEmailAddress(_, email) => {
-                    unreachable_email::handle(file, uri, email)
-                }
-                // The given header could not be parsed.
-                ErrorKind::InvalidHeader(header) => {
-                    debug!("{:#?} request error unable to parse HTTP header, problematic header: {:#?}", &uri, &header);
-                    vec![]
+                    unreachable_email::handle(file, uri, positions, email)
                 }
                 // The given string can not be parsed into a valid base URL or base directory
                 ErrorKind::InvalidBase(base_dir, url) => {
@@ -142,39 +208,11 @@ pub fn handle_response(
                     );
                     vec![]
                 }
-                // The Github API could not be called because of a missing Github token.
-                ErrorKind::MissingGitHubToken => {
-                    debug!("{:#?} request error missing GitHub API token", &uri);
-                    vec![]
-                }
-                // Used an insecure URI where a secure variant was reachable
-                ErrorKind::InsecureURL(uri) => {
-                    debug!("{:#?} request error used an insecure URI where a secure variant was reachable {:#?}", &uri, &uri);
-                    vec![]
-                }
                 // Error while sending/receiving messages from MPSC channel
                 ErrorKind::Channel(send_error) => {
                     debug!("{:#?} request error while sending/receiving messages from MPSC channel, error:\n{:#?}", &uri, &send_error);
                     vec![]
                 }
-                // An URL with an invalid host was found
-                ErrorKind::InvalidUrlHost => {
-                    debug!(
-                        "{:#?} request error URL with an invalid host was found",
-                        &uri
-                    );
-                    vec![]
-                }
-                // Cannot parse the given URI
-                ErrorKind::InvalidURI(uri) => {
-                    debug!("{:#?} request error cannot parse the given URI", &uri);
-                    vec![]
-                }
-                // The given status code is invalid (not in the range 100-1000)
-                ErrorKind::InvalidStatusCode(status_code) => {
-                    debug!("{:#?} request error The given status code is invalid (not in the range 100-1000), status code: {:#?}", &uri, &status_code);
-                    vec![]
-                }
                 // Regex error
                 ErrorKind::Regex(regex_error) => {
                     debug!(
@@ -183,25 +221,21 @@ pub fn handle_response(
                     );
                     vec![]
                 }
-                // Too many redirects (HTTP 3xx) were encountered (configurable)
-                ErrorKind::TooManyRedirects(error) => {
-                    debug!(
-                        "{:#?} request error to many redirects, error:\n{:#?}",
-                        &uri, &error
-                    );
-                    vec![]
-                }
-                // WTF???
-                _ => {
-                    debug!("{:#?} unknown error", &uri);
-                    vec![]
-                }
+                // Errors of the link itself, e.g. too many redirects or invalid URL
+                error_kind => match link_error::describe(uri, error_kind) {
+                    Some(error) => link_error::handle(file, uri, positions, &error, config),
+                    None => {
+                        debug!("{:#?} unknown error", &uri);
+                        vec![]
+                    }
+                },
             }
         }
         // Request timed out
         Status::Timeout(_) => request_timeout::handle(
             file,
             uri,
+            positions,
             client_config.timeout.as_secs(),
             client_config.max_retries,
         ),
@@ -215,11 +249,8 @@ pub fn handle_response(
         }
         // The given status code is not known by lychee
         Status::UnknownStatusCode(status_code) => {
-            debug!(
-                "{:#?} request error unknown status code: {:#?}",
-                &uri, &status_code
-            );
-            vec![]
+            let error = link_error::describe_unknown_status_code(uri, status_code);
+            link_error::handle(file, uri, positions, &error, config)
         }
         // Resource was excluded from checking
         Status::Excluded => {
@@ -240,7 +271,12 @@ pub fn handle_response(
                 "{:#?} request cached, cache status: {:#?}",
                 &uri, &cache_status
             );
-            vec![]
+            match cache_status {
+                CacheStatus::Error(status_code) => {
+                    cached_error::handle(file, uri, positions, status_code)
+                }
+                _ => vec![],
+            }
         }
     }
 }
@@ -1,23 +1,21 @@
-use super::utils::find_all_links_in_file;
+use super::utils::issue_at;
 use colored::Colorize;
-use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity, MarkDownFile};
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
 use log::debug;
+use markdown::unist::Position;
 use reqwest::StatusCode;
 
-pub fn handle(file: &MarkDownFile, uri: &str, error: &reqwest::Error) -> Vec<CheckIssue> {
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    error: &reqwest::Error,
+) -> Vec<CheckIssue> {
     debug!("{uri} - handling network error: {error}");
     let mut issues: Vec<CheckIssue> = vec![];
-    for offset in find_all_links_in_file(file, uri) {
-        let mut issue = CheckIssueBuilder::default()
-            .set_category(IssueCategory::LinkChecking)
+    for position in positions {
+        let mut issue = issue_at(file, position)
             .set_severity(IssueSeverity::Warning)
-            .set_file_path(file.path.clone())
-            .set_row_num_start(1)
-            .set_row_num_end(file.content.lines().count())
-            .set_col_num_start(1)
-            .set_col_num_end(1)
-            .set_offset_start(offset.start)
-            .set_offset_end(offset.end)
             .set_message(format!("{error}"));
         issue = issue.push_fix(&format!(
             "🧠 {}  {}",
@@ -0,0 +1,40 @@
+use super::utils::issue_at;
+use crate::redirect::RedirectChain;
+use colored::Colorize;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    chain: &RedirectChain,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling permanent redirect: {}", chain.describe());
+    let target = match chain.permanent_target() {
+        Some(target) => target,
+        None => return vec![],
+    };
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let mut issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Warning)
+            .set_message(format!("Link {uri} is permanently redirected to {target}"));
+        issue = issue.push_fix(&format!(
+            "🧠 {}  {}",
+            "Rationale".cyan(),
+            "Permanent redirects are often removed after a while and the link breaks"
+        ));
+        let fixes = vec![
+            format!("Replace the link with {target}"),
+            format!("Run link checker with --fix-redirects to replace it automatically"),
+            format!("Redirect chain: {}", chain.describe()),
+        ];
+        for fix in fixes {
+            issue = issue.push_fix(&format!("💡 {} {}", "Suggestion".cyan(), fix));
+        }
+        issues.push(issue.build());
+    }
+    issues
+}
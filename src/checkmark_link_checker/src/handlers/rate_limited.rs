@@ -0,0 +1,28 @@
+use super::utils::issue_at;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    max_retries: u64,
+) -> Vec<CheckIssue> {
+    debug!("{uri} - handling rate limited request");
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Note)
+            .set_message(format!(
+                "Link {uri} is not verified, server is rate limiting requests"
+            ))
+            .set_fixes(vec![
+                format!("Consider increasing maximum amount of retries in config file, currently its set to {max_retries}"),
+                format!("Consider increasing min_delay_per_host in the [link_checker.rate_limit] section of the config"),
+                format!("If this is a GitHub link, set --github-token CLI option to get a higher rate limit"),
+            ]);
+        issues.push(issue.build());
+    }
+    issues
+}
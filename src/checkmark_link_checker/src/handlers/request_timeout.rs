@@ -1,21 +1,20 @@
-use super::utils::find_all_links_in_file;
-use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity, MarkDownFile};
+use super::utils::issue_at;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
 use log::debug;
+use markdown::unist::Position;
 
-pub fn handle(file: &MarkDownFile, uri: &str, timeout: u64, max_retries: u64) -> Vec<CheckIssue> {
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    timeout: u64,
+    max_retries: u64,
+) -> Vec<CheckIssue> {
     debug!("{uri} - handling request timeout");
     let mut issues: Vec<CheckIssue> = vec![];
-    for offset in find_all_links_in_file(file, uri) {
-        let issue = CheckIssueBuilder::default()
-            .set_category(IssueCategory::LinkChecking)
+    for position in positions {
+        let issue = issue_at(file, position)
             .set_severity(IssueSeverity::Warning)
-            .set_file_path(file.path.clone())
-            .set_row_num_start(1)
-            .set_row_num_end(file.content.lines().count())
-            .set_col_num_start(1)
-            .set_col_num_end(1)
-            .set_offset_start(offset.start)
-            .set_offset_end(offset.end)
             .set_message(format!("Request timeout for url {uri}"))
             .set_fixes(vec![
                 format!("Consider increasing timeout in config file, currently its set to {timeout} seconds"),
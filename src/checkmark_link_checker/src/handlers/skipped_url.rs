@@ -0,0 +1,19 @@
+use super::utils::issue_at;
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
+use log::debug;
+use markdown::unist::Position;
+
+pub fn handle(file: &MarkDownFile, uri: &str, positions: &[Position]) -> Vec<CheckIssue> {
+    debug!("{uri} - listing skipped external URL");
+    let mut issues: Vec<CheckIssue> = vec![];
+    for position in positions {
+        let issue = issue_at(file, position)
+            .set_severity(IssueSeverity::Note)
+            .set_message(format!("External URL {uri} is not checked in offline mode"))
+            .set_fixes(vec![format!(
+                "Run link checker without --offline to verify this URL"
+            )]);
+        issues.push(issue.build());
+    }
+    issues
+}
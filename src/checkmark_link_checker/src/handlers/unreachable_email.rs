@@ -1,22 +1,20 @@
-use super::utils::find_all_links_in_file;
+use super::utils::issue_at;
 use colored::Colorize;
-use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity, MarkDownFile};
+use common::{CheckIssue, IssueSeverity, MarkDownFile};
 use log::debug;
+use markdown::unist::Position;
 
-pub fn handle(file: &MarkDownFile, uri: &str, error_message: &str) -> Vec<CheckIssue> {
+pub fn handle(
+    file: &MarkDownFile,
+    uri: &str,
+    positions: &[Position],
+    error_message: &str,
+) -> Vec<CheckIssue> {
     debug!("{:#?} - handling unreachable E-Mail error", &uri);
     let mut issues: Vec<CheckIssue> = vec![];
-    for offset in find_all_links_in_file(file, uri) {
-        let mut issue = CheckIssueBuilder::default()
-            .set_category(IssueCategory::LinkChecking)
+    for position in positions {
+        let mut issue = issue_at(file, position)
             .set_severity(IssueSeverity::Warning)
-            .set_file_path(file.path.clone())
-            .set_row_num_start(1)
-            .set_row_num_end(file.content.lines().count())
-            .set_col_num_start(1)
-            .set_col_num_end(1)
-            .set_offset_start(offset.start)
-            .set_offset_end(offset.end)
             .set_message(format!(
                 "Unable to verify an e-mail \"{uri}\". Reason: {error_message}"
             ));
@@ -0,0 +1,33 @@
+use colored::Colorize;
+use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity};
+use log::debug;
+use std::path::Path;
+
+/// Unused asset is not a part of any document, so issue points to the asset itself
+pub fn handle(path: &Path) -> CheckIssue {
+    debug!("{path:#?} - handling unused asset");
+    CheckIssueBuilder::default()
+        .set_category(IssueCategory::LinkChecking)
+        .set_file_path(path.to_string_lossy().to_string())
+        .set_row_num_start(1)
+        .set_row_num_end(1)
+        .set_col_num_start(1)
+        .set_col_num_end(1)
+        .set_offset_start(0)
+        .set_offset_end(0)
+        .set_severity(IssueSeverity::Warning)
+        .set_message(format!(
+            "Asset \"{}\" is not linked from any document",
+            path.display()
+        ))
+        .push_fix(&format!(
+            "🧠 {}  {}",
+            "Rationale".cyan(),
+            "Unused files make the repository bigger and are often left behind after documents are changed"
+        ))
+        .push_fix(&format!(
+            "💡 {} Remove the file or add it to \"ignore\" in the [link_checker.assets] section of the config",
+            "Suggestion".cyan()
+        ))
+        .build()
+}
@@ -1,12 +1,15 @@
-use common::MarkDownFile;
-use std::ops::Range;
+use common::{CheckIssueBuilder, IssueCategory, MarkDownFile};
+use markdown::unist::Position;
 
-pub fn find_all_links_in_file(file: &MarkDownFile, uri: &str) -> Vec<Range<usize>> {
-    file.content
-        .match_indices(&uri)
-        .map(|(offset, matched_str)| Range {
-            start: offset,
-            end: offset + matched_str.len(),
-        })
-        .collect()
+/// Link checking issue located at the position of the link in the file
+pub fn issue_at(file: &MarkDownFile, position: &Position) -> CheckIssueBuilder {
+    CheckIssueBuilder::default()
+        .set_category(IssueCategory::LinkChecking)
+        .set_file_path(file.path.clone())
+        .set_row_num_start(position.start.line)
+        .set_row_num_end(position.end.line)
+        .set_col_num_start(position.start.column)
+        .set_col_num_end(position.end.column)
+        .set_offset_start(position.start.offset)
+        .set_offset_end(position.end.offset)
 }
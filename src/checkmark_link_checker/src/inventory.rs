@@ -0,0 +1,230 @@
+use crate::locator::LinkLocator;
+use crate::offline::OfflineStatus;
+use crate::redirect::RedirectChain;
+use crate::root::RootResolver;
+use common::MarkDownFile;
+use lychee_lib::{CacheStatus, ErrorKind, Request, Status};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Web,
+    Mail,
+    File,
+    /// Fragment of the same document, e.g. "#installation"
+    Anchor,
+}
+
+impl LinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::Web => "web",
+            LinkKind::Mail => "mail",
+            LinkKind::File => "file",
+            LinkKind::Anchor => "anchor",
+        }
+    }
+}
+
+/// Place in the file where the link is used
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct LinkReference {
+    pub path: String,
+    pub line: usize,
+}
+
+/// Unique link together with the result of its check and every place where it is used.
+/// Check results are empty when links are only collected
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InventoryEntry {
+    pub uri: String,
+    pub kind: LinkKind,
+    pub status: Option<String>,
+    pub http_code: Option<u16>,
+    pub redirect_target: Option<String>,
+    pub response_time_ms: Option<u64>,
+    pub references: Vec<LinkReference>,
+}
+
+impl InventoryEntry {
+    /// Result of the link check, response time is not known for cached results
+    pub fn set_status(&mut self, status: &Status, response_time: Option<Duration>) {
+        let (name, code) = match status {
+            Status::Ok(code) => ("ok", Some(code.as_u16())),
+            Status::Redirected(code) => ("redirected", Some(code.as_u16())),
+            Status::Error(ErrorKind::NetworkRequest(error)) => {
+                ("error", error.status().map(|code| code.as_u16()))
+            }
+            Status::Error(_) => ("error", None),
+            Status::UnknownStatusCode(code) => ("error", Some(code.as_u16())),
+            Status::Timeout(code) => ("timeout", code.map(|code| code.as_u16())),
+            Status::Excluded => ("excluded", None),
+            Status::Unsupported(_) => ("unsupported", None),
+            Status::Cached(CacheStatus::Ok(code)) => ("ok", Some(*code)),
+            Status::Cached(CacheStatus::Error(code)) => ("error", *code),
+            Status::Cached(_) => ("excluded", None),
+        };
+        self.status = Some(String::from(name));
+        self.http_code = code;
+        self.response_time_ms = response_time.map(|time| time.as_millis() as u64);
+    }
+
+    pub fn set_offline_status(&mut self, status: &OfflineStatus) {
+        let name = match status {
+            OfflineStatus::Ok => "ok",
+            OfflineStatus::Skipped => "skipped",
+            OfflineStatus::MissingPath(_) => "missing",
+            OfflineStatus::MissingFragment(_, _) => "missing_fragment",
+            OfflineStatus::InvalidEmail(_) => "invalid_email",
+        };
+        self.status = Some(String::from(name));
+    }
+
+    /// Final URL of the redirect chain
+    pub fn set_redirect(&mut self, chain: &RedirectChain) {
+        self.redirect_target = chain.hops.last().map(|hop| hop.location.to_string());
+    }
+
+    pub fn set_invalid_email(&mut self) {
+        self.status = Some(String::from("invalid_email"));
+    }
+
+    pub fn set_rate_limited(&mut self) {
+        self.status = Some(String::from("rate_limited"));
+        self.http_code = Some(429);
+    }
+}
+
+/// Every unique link of the files with the places where it is used, sorted by URI
+pub fn collect(
+    files: &[MarkDownFile],
+    links: &[HashMap<String, Request>],
+    resolver: &RootResolver,
+) -> BTreeMap<String, InventoryEntry> {
+    let mut inventory: BTreeMap<String, InventoryEntry> = BTreeMap::new();
+    for (file, file_links) in files.iter().zip(links) {
+        let locator = LinkLocator::new(file, resolver);
+        for (uri, request) in file_links {
+            let entry = inventory
+                .entry(uri.clone())
+                .or_insert_with(|| InventoryEntry {
+                    uri: crate::sanitize_uri(uri),
+                    kind: kind(file, request),
+                    status: None,
+                    http_code: None,
+                    redirect_target: None,
+                    response_time_ms: None,
+                    references: vec![],
+                });
+            entry
+                .references
+                .extend(
+                    locator
+                        .locate(&request.uri)
+                        .into_iter()
+                        .map(|position| LinkReference {
+                            path: file.path.clone(),
+                            line: position.start.line,
+                        }),
+                );
+        }
+    }
+    inventory
+}
+
+fn kind(file: &MarkDownFile, request: &Request) -> LinkKind {
+    if request.uri.is_mail() {
+        return LinkKind::Mail;
+    }
+    if !request.uri.is_file() {
+        return LinkKind::Web;
+    }
+    let mut url = request.uri.url.clone();
+    let has_fragment = url.fragment().is_some();
+    url.set_fragment(None);
+    let is_same_file = url
+        .to_file_path()
+        .is_ok_and(|path| std::path::absolute(&file.path).is_ok_and(|file| file == path));
+    match has_fragment && is_same_file {
+        true => LinkKind::Anchor,
+        false => LinkKind::File,
+    }
+}
+
+pub fn to_json(entries: &[InventoryEntry]) -> String {
+    serde_json::to_string_pretty(entries).unwrap()
+}
+
+/// One row per link, references are joined into a single "path:line;path:line" column
+pub fn to_csv(entries: &[InventoryEntry]) -> String {
+    let mut csv =
+        String::from("uri,kind,status,http_code,redirect_target,response_time_ms,references\n");
+    for entry in entries {
+        let references = entry
+            .references
+            .iter()
+            .map(|reference| format!("{}:{}", reference.path, reference.line))
+            .collect::<Vec<String>>()
+            .join(";");
+        let row = [
+            entry.uri.clone(),
+            entry.kind.as_str().to_string(),
+            entry.status.clone().unwrap_or_default(),
+            entry.http_code.map(|c| c.to_string()).unwrap_or_default(),
+            entry.redirect_target.clone().unwrap_or_default(),
+            entry
+                .response_time_ms
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            references,
+        ];
+        let row = row
+            .iter()
+            .map(|field| escape_csv(field))
+            .collect::<Vec<String>>();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_csv(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inventory_exported_to_csv() {
+        let entries = vec![InventoryEntry {
+            uri: String::from("https://example.com/a,b"),
+            kind: LinkKind::Web,
+            status: Some(String::from("ok")),
+            http_code: Some(200),
+            redirect_target: None,
+            response_time_ms: Some(42),
+            references: vec![
+                LinkReference {
+                    path: String::from("README.md"),
+                    line: 3,
+                },
+                LinkReference {
+                    path: String::from("docs/guide.md"),
+                    line: 10,
+                },
+            ],
+        }];
+        assert_eq!(
+            "uri,kind,status,http_code,redirect_target,response_time_ms,references\n\
+             \"https://example.com/a,b\",web,ok,200,,42,README.md:3;docs/guide.md:10\n",
+            to_csv(&entries)
+        );
+    }
+}
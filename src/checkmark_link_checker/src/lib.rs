@@ -1,14 +1,47 @@
+mod assets;
+mod cache;
 mod client_config;
 mod collector;
+mod concurrency;
+mod cookies;
+mod direct;
+mod email;
+mod fix;
 mod handlers;
+mod inventory;
+mod locator;
+mod offline;
+mod redirect;
+mod remap;
+mod remote_fragment;
+mod retry;
+mod root;
 
+use cache::LinkCache;
 use client_config::*;
 use collector::*;
+use colored::Colorize;
 use common::{CheckIssue, Config, MarkDownFile};
+use concurrency::HostLimiter;
+use email::{EmailStatus, EmailValidator};
+pub use fix::{apply_fixes, LinkFix};
 use futures::future::join_all;
+use futures::StreamExt;
 use handlers::*;
+pub use inventory::{to_csv, to_json, InventoryEntry, LinkKind, LinkReference};
+use locator::LinkLocator;
 use log::debug;
-use lychee_lib::ClientBuilder;
+use lychee_lib::{Client, ClientBuilder, Request, Response, Status, Uri};
+use markdown::unist::Position;
+use redirect::RedirectChain;
+use remap::Remapper;
+use remote_fragment::FragmentChecker;
+pub use root::project_root_dir;
+use root::RootResolver;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 fn sanitize_uri(uri: &str) -> String {
     let mut uri = uri.to_string();
@@ -22,47 +55,564 @@ fn sanitize_uri(uri: &str) -> String {
 }
 
 pub async fn check(file: &MarkDownFile, config: &Config) -> Vec<CheckIssue> {
-    debug!("Checking: {:#?}, config: {:#?}", &file.path, &config);
-    let links = collect(&file.path, config).await.unwrap();
-    let client_config = ClientConfig::from_checkmark_config(config);
-    let requests = links.iter().map(|(uri, request)| {
-        let client = ClientBuilder::builder()
-            .allow_insecure(client_config.allow_insecure)
-            .timeout(client_config.timeout)
-            .include_mail(client_config.check_emails)
-            .accepted(client_config.accepted_status_codes.clone())
-            .max_retries(client_config.max_retries)
-            .github_token(client_config.github_token.clone())
-            .user_agent(client_config.user_agent.clone())
-            .build()
-            .client()
-            .unwrap();
-        let uri = sanitize_uri(uri);
-        debug!("Checking {:#?}", &uri);
-        async move { (uri, client.check(request.clone()).await.unwrap()) }
-    });
-    join_all(requests)
+    bulk_check(std::slice::from_ref(file), config)
         .await
-        .iter()
-        .flat_map(|(uri, response)| handle_response(file, uri, response, &client_config))
-        .collect::<Vec<CheckIssue>>()
+        .into_iter()
+        .flat_map(|result| result.issues)
+        .collect()
 }
 
 pub struct BulkCheckResult {
+    /// Path of the checked file
     pub path: String,
     pub issues: Vec<CheckIssue>,
+    /// Replacements of redirected links, only when fixing of redirects is enabled
+    pub fixes: Vec<LinkFix>,
+}
+
+/// File in the asset directories that no document links to
+pub struct UnusedAsset {
+    pub path: PathBuf,
+    /// Issue about the asset itself, it does not point into any document
+    pub issue: CheckIssue,
+}
+
+/// Results of checking links of all files
+pub struct LinkCheckReport {
+    pub results: Vec<BulkCheckResult>,
+    /// Only when asset directories are configured
+    pub unused_assets: Vec<UnusedAsset>,
+    /// Every unique link of the files with its result
+    pub inventory: Vec<InventoryEntry>,
+}
+
+/// Result of checking a single link
+struct LinkResult {
+    response: Response,
+    /// Redirects that happened while requesting the link
+    redirect: Option<RedirectChain>,
+    /// Fragment that is not found on the remote page
+    missing_fragment: Option<String>,
+    /// Server kept rate limiting requests, so the link is not verified
+    rate_limited: bool,
+    /// Time it took to check the link, None for cached results
+    response_time: Option<Duration>,
+    /// Why the e-mail address is invalid, when it is validated without SMTP
+    invalid_email: Option<EmailStatus>,
 }
 
+/// Check links of all files. Every unique link is checked only once,
+/// no matter how many files reference it, and its result is reported in each of them
 pub async fn bulk_check(files: &[MarkDownFile], config: &Config) -> Vec<BulkCheckResult> {
-    let checks = files.iter().map(|file| {
-        let file_path = file.path.clone();
-        let config_clone = config.clone();
-        async move {
-            BulkCheckResult {
-                path: file_path.clone(),
-                issues: check(file, &config_clone).await,
+    bulk_check_with_inventory(files, config).await.results
+}
+
+/// Every unique link of the files and the places where it is used, without checking it
+pub async fn collect_inventory(files: &[MarkDownFile], config: &Config) -> Vec<InventoryEntry> {
+    let resolver = RootResolver::new(config);
+    let links = collect_all(files, config, &resolver).await;
+    inventory::collect(files, &links, &resolver)
+        .into_values()
+        .collect()
+}
+
+async fn collect_all(
+    files: &[MarkDownFile],
+    config: &Config,
+    resolver: &RootResolver,
+) -> Vec<HashMap<String, Request>> {
+    join_all(
+        files
+            .iter()
+            .map(|file| collect(&file.path, config, resolver)),
+    )
+    .await
+    .into_iter()
+    .map(|links| links.unwrap())
+    .collect()
+}
+
+/// Same as `bulk_check`, but also returns unused assets
+/// and the inventory of all unique links with their results
+pub async fn bulk_check_with_inventory(files: &[MarkDownFile], config: &Config) -> LinkCheckReport {
+    debug!("Checking {} files, config: {:#?}", files.len(), &config);
+    let resolver = RootResolver::new(config);
+    let links = collect_all(files, config, &resolver).await;
+    let mut inventory = inventory::collect(files, &links, &resolver);
+    // Links are checked after remapping, but reported with their original URL
+    let remapper = Remapper::new(config, resolver.root_dir());
+    let mut unique_links: HashMap<&String, Request> = HashMap::new();
+    for (uri, request) in links.iter().flatten() {
+        unique_links
+            .entry(uri)
+            .or_insert_with(|| remapper.remap_request(request));
+    }
+    let remapped = links
+        .iter()
+        .flatten()
+        .filter(|(uri, request)| unique_links[uri].uri != request.uri)
+        .map(|(uri, _)| (uri, unique_links[uri].uri.clone()))
+        .collect::<HashMap<&String, Uri>>();
+    debug!(
+        "Found {} unique links in {} files",
+        unique_links.len(),
+        files.len()
+    );
+    // Local links that work only on case-insensitive file systems
+    let case_mismatches = unique_links
+        .iter()
+        .filter_map(|(uri, request)| Some((*uri, to_file_path(&request.uri)?)))
+        .filter_map(|(uri, path)| Some((uri, assets::find_case_mismatch(&path)?)))
+        .collect::<HashMap<&String, PathBuf>>();
+    let unused_assets = unused_assets(&links, &case_mismatches, &resolver, config);
+
+    let email_validator = config
+        .link_checker
+        .check_emails
+        .then(|| EmailValidator::new(&config.link_checker.emails));
+
+    if config.link_checker.offline {
+        let statuses = unique_links
+            .into_iter()
+            .map(|(uri, request)| (uri, offline::check(&request, email_validator.as_ref())))
+            .collect::<HashMap<&String, offline::OfflineStatus>>();
+        for (uri, status) in &statuses {
+            inventory.get_mut(*uri).unwrap().set_offline_status(status);
+        }
+        let results = fan_out(
+            files,
+            &links,
+            &remapped,
+            &resolver,
+            |file, uri, positions| {
+                if let Some(actual) = case_mismatches.get(uri) {
+                    return handle_case_mismatch(file, &sanitize_uri(uri), positions, actual);
+                }
+                handle_offline_status(file, &sanitize_uri(uri), positions, &statuses[uri], config)
+            },
+        );
+        return LinkCheckReport {
+            results,
+            unused_assets,
+            inventory: inventory.into_values().collect(),
+        };
+    }
+
+    // Local files need no network, so they are checked the same way as offline,
+    // e.g. link to a directory opens its index file and the fragment is looked up in it
+    let (local_links, web_links): (HashMap<&String, Request>, HashMap<&String, Request>) =
+        unique_links
+            .into_iter()
+            .partition(|(_, request)| request.uri.is_file());
+    let local_statuses = local_links
+        .into_iter()
+        .map(|(uri, request)| (uri, offline::check(&request, None)))
+        .collect::<HashMap<&String, offline::OfflineStatus>>();
+    for (uri, status) in &local_statuses {
+        inventory.get_mut(*uri).unwrap().set_offline_status(status);
+    }
+    let client_config = ClientConfig::from_checkmark_config(config);
+    let results = check_links(web_links, &client_config, email_validator.as_ref(), config).await;
+    for (uri, result) in &results {
+        let entry = inventory.get_mut(*uri).unwrap();
+        entry.set_status(result.response.status(), result.response_time);
+        if result.invalid_email.is_some() {
+            entry.set_invalid_email();
+        }
+        if result.rate_limited {
+            entry.set_rate_limited();
+        }
+        if let Some(chain) = &result.redirect {
+            entry.set_redirect(chain);
+        }
+    }
+    let mut bulk_results = fan_out(
+        files,
+        &links,
+        &remapped,
+        &resolver,
+        |file, uri, positions| {
+            if let Some(actual) = case_mismatches.get(uri) {
+                return handle_case_mismatch(file, &sanitize_uri(uri), positions, actual);
+            }
+            if let Some(status) = local_statuses.get(uri) {
+                return handle_offline_status(file, &sanitize_uri(uri), positions, status, config);
+            }
+            if let Some(status) = &results[uri].invalid_email {
+                return handle_invalid_email(file, &sanitize_uri(uri), positions, status);
             }
+            if results[uri].rate_limited {
+                return handle_rate_limited(file, &sanitize_uri(uri), positions, &client_config);
+            }
+            let mut issues = handle_response(
+                file,
+                &sanitize_uri(uri),
+                positions,
+                &results[uri].response,
+                &client_config,
+                config,
+            );
+            if let Some(fragment) = &results[uri].missing_fragment {
+                issues.append(&mut handle_missing_remote_fragment(
+                    file,
+                    &sanitize_uri(uri),
+                    positions,
+                    fragment,
+                ));
+            }
+            if let Some(chain) = &results[uri].redirect {
+                issues.append(&mut handle_redirect(
+                    file,
+                    &sanitize_uri(uri),
+                    positions,
+                    chain,
+                    config,
+                ));
+            }
+            issues
+        },
+    );
+    if config.link_checker.redirects.fix {
+        for (bulk_result, (file, file_links)) in
+            bulk_results.iter_mut().zip(files.iter().zip(&links))
+        {
+            bulk_result.fixes = redirect_fixes(file, file_links, &results, &resolver, config);
         }
-    });
-    join_all(checks).await
+    }
+    LinkCheckReport {
+        results: bulk_results,
+        unused_assets,
+        inventory: inventory.into_values().collect(),
+    }
+}
+
+/// Path of the local file the URI points to
+fn to_file_path(uri: &Uri) -> Option<PathBuf> {
+    if !uri.is_file() {
+        return None;
+    }
+    let mut url = uri.url.clone();
+    url.set_fragment(None);
+    url.to_file_path().ok()
+}
+
+/// Files in the asset directories that no document links to
+fn unused_assets(
+    links: &[HashMap<String, Request>],
+    case_mismatches: &HashMap<&String, PathBuf>,
+    resolver: &RootResolver,
+    config: &Config,
+) -> Vec<UnusedAsset> {
+    if config.link_checker.assets.dirs.is_empty() {
+        return vec![];
+    }
+    let referenced = links
+        .iter()
+        .flatten()
+        .filter_map(|(uri, request)| {
+            case_mismatches
+                .get(uri)
+                .cloned()
+                .or_else(|| to_file_path(&request.uri))
+        })
+        .filter_map(|path| std::fs::canonicalize(path).ok())
+        .collect::<HashSet<PathBuf>>();
+    assets::find_unused(
+        &config.link_checker.assets,
+        resolver.root_dir(),
+        &referenced,
+    )
+    .into_iter()
+    .map(|path| UnusedAsset {
+        issue: handle_unused_asset(&path),
+        path,
+    })
+    .collect()
+}
+
+/// Replace every permanently redirected link with the URL it moved to
+fn redirect_fixes(
+    file: &MarkDownFile,
+    file_links: &HashMap<String, Request>,
+    results: &HashMap<&String, LinkResult>,
+    resolver: &RootResolver,
+    config: &Config,
+) -> Vec<LinkFix> {
+    let locator = LinkLocator::new(file, resolver);
+    let mut fixes: Vec<LinkFix> = vec![];
+    for (uri, request) in file_links {
+        let chain = match results.get(uri).and_then(|result| result.redirect.as_ref()) {
+            Some(chain) if chain.is_reportable(&config.link_checker.redirects) => chain,
+            _ => continue,
+        };
+        let target = chain.permanent_target().unwrap();
+        for (from, position) in locator.occurrences(&request.uri) {
+            let mut to = target.to_string();
+            // Fragment is handled by the browser, so it is kept as it is
+            if let (Some((_, fragment)), None) = (from.split_once('#'), target.fragment()) {
+                to = format!("{to}#{fragment}");
+            }
+            fixes.push(LinkFix { position, from, to });
+        }
+    }
+    fixes
+}
+
+/// Report results of unique links in every file that references them,
+/// at the position of each occurrence of the link
+fn fan_out(
+    files: &[MarkDownFile],
+    links: &[HashMap<String, Request>],
+    remapped: &HashMap<&String, Uri>,
+    resolver: &RootResolver,
+    handle: impl Fn(&MarkDownFile, &String, &[Position]) -> Vec<CheckIssue>,
+) -> Vec<BulkCheckResult> {
+    files
+        .iter()
+        .zip(links)
+        .map(|(file, file_links)| {
+            let locator = LinkLocator::new(file, resolver);
+            BulkCheckResult {
+                path: file.path.clone(),
+                issues: file_links
+                    .iter()
+                    .flat_map(|(uri, request)| {
+                        let mut issues = handle(file, uri, &locator.locate(&request.uri));
+                        if let Some(checked) = remapped.get(uri) {
+                            for issue in issues.iter_mut() {
+                                issue.fixes.push(format!(
+                                    "🔀 {}   {uri} was checked as {checked}",
+                                    "Remapped".cyan()
+                                ));
+                            }
+                        }
+                        issues
+                    })
+                    .collect(),
+                fixes: vec![],
+            }
+        })
+        .collect()
+}
+
+/// Check links with a single client, limiting the number of concurrent requests
+/// globally and per host. Results of previous runs are taken from the cache.
+/// When redirects are reported or fixed, lychee does not follow them, and only
+/// redirected links are requested again to record their chain and check where it ends.
+/// Web links with fragments are checked by fetching their pages, with the same request.
+/// E-mails are validated by the validator first and only unverified ones are sent to SMTP
+async fn check_links<'a>(
+    links: HashMap<&'a String, Request>,
+    client_config: &ClientConfig,
+    email_validator: Option<&EmailValidator>,
+    config: &Config,
+) -> HashMap<&'a String, LinkResult> {
+    let follows_redirects = redirect::follows(&config.link_checker.redirects);
+    // Client per set of headers, since they can't be set for a single request
+    let headers_keys = links
+        .iter()
+        .map(|(uri, request)| (*uri, client_config.headers_key(&request.uri.url)))
+        .collect::<HashMap<&String, HeadersKey>>();
+    let clients = headers_keys
+        .values()
+        .collect::<HashSet<&HeadersKey>>()
+        .into_iter()
+        .map(|key| {
+            let headers = client_config.headers(key);
+            (key, build_client(client_config, headers, follows_redirects))
+        })
+        .collect::<HashMap<&HeadersKey, Client>>();
+    let cache = Mutex::new(LinkCache::load(&config.link_checker.cache));
+    let limiter = HostLimiter::new(
+        client_config.max_concurrency_per_host,
+        client_config.min_delay_per_host,
+    );
+    let redirect_client = redirect::build_client(client_config);
+    let fragment_checker = FragmentChecker::new(client_config, follows_redirects);
+    let http_client = direct::build_client(client_config, follows_redirects);
+    let results = futures::stream::iter(links)
+        .map(|(uri, request)| {
+            let client = &clients[&headers_keys[uri]];
+            let (cache, limiter) = (&cache, &limiter);
+            let (redirect_client, fragment_checker) = (&redirect_client, &fragment_checker);
+            let http_client = &http_client;
+            async move {
+                let is_remote = !request.uri.is_file() && !request.uri.is_mail();
+                // Cache does not know about fragments, so pages are fetched to check them
+                let checks_fragment = config.link_checker.remote_fragments
+                    && is_remote
+                    && request.uri.url.fragment().is_some();
+                let cached = match checks_fragment {
+                    true => None,
+                    false => cache.lock().unwrap().get(&request.uri, follows_redirects),
+                };
+                let email = match (email_validator, request.uri.is_mail()) {
+                    (Some(validator), true) => Some(validator.validate(request.uri.as_str()).await),
+                    _ => None,
+                };
+                let mut redirect: Option<RedirectChain> = None;
+                let mut missing_fragment: Option<String> = None;
+                let mut rate_limited = false;
+                let mut response_time: Option<Duration> = None;
+                let response = match cached {
+                    Some(status) => {
+                        debug!("Using cached result for {:#?}: {:#?}", &uri, &status);
+                        Response::new(
+                            request.uri.clone(),
+                            Status::Cached(status),
+                            request.source.clone(),
+                        )
+                    }
+                    None if email
+                        .as_ref()
+                        .is_some_and(|e| *e != EmailStatus::Unverified) =>
+                    {
+                        let status = match email {
+                            Some(EmailStatus::Valid) => Status::Ok(reqwest::StatusCode::OK),
+                            _ => Status::Excluded,
+                        };
+                        Response::new(request.uri.clone(), status, request.source.clone())
+                    }
+                    None => {
+                        let _permit = limiter.acquire(&request.uri).await;
+                        debug!("Checking {:#?}", &uri);
+                        let started = Instant::now();
+                        // Page of the fragment is fetched anyway, so its response is the verdict
+                        let page = match checks_fragment {
+                            true => fragment_checker.check(&request.uri.url).await,
+                            false => None,
+                        };
+                        let status = match page {
+                            Some(page) => {
+                                redirect = page.redirect;
+                                missing_fragment = page.missing_fragment;
+                                Status::Ok(page.status)
+                            }
+                            None => check_request(client, request.clone()).await,
+                        };
+                        let mut response =
+                            Response::new(request.uri.clone(), status, request.source.clone());
+                        response_time = Some(started.elapsed());
+                        if retry::is_rate_limited(response.status()) {
+                            let check = || check_request(client, request.clone());
+                            match retry::retry(
+                                http_client,
+                                client_config,
+                                limiter,
+                                &request.uri,
+                                check,
+                            )
+                            .await
+                            {
+                                Some(status) if retry::is_rate_limited(&status) => {
+                                    rate_limited = true
+                                }
+                                Some(status) => {
+                                    response = Response::new(
+                                        request.uri.clone(),
+                                        status,
+                                        request.source.clone(),
+                                    )
+                                }
+                                None => rate_limited = true,
+                            }
+                        }
+                        if follows_redirects
+                            && is_remote
+                            && redirect::is_redirected(response.status())
+                        {
+                            redirect =
+                                redirect::follow(redirect_client, client_config, &request.uri.url)
+                                    .await;
+                            // Verdict on the link is the verdict on the URL where it ends
+                            if let Some(chain) = &redirect {
+                                let target = Request::new(
+                                    Uri::from(chain.target().clone()),
+                                    request.source.clone(),
+                                    None,
+                                    None,
+                                );
+                                let status = check_request(client, target).await;
+                                response = Response::new(
+                                    request.uri.clone(),
+                                    status,
+                                    request.source.clone(),
+                                );
+                            }
+                        }
+                        // Redirected links are checked on every run, so they keep being reported
+                        if redirect.is_none()
+                            && !rate_limited
+                            && !redirect::is_redirected(response.status())
+                        {
+                            cache.lock().unwrap().insert(
+                                &request.uri,
+                                response.status(),
+                                follows_redirects,
+                            );
+                        }
+                        response
+                    }
+                };
+                (
+                    uri,
+                    LinkResult {
+                        response,
+                        redirect,
+                        missing_fragment,
+                        rate_limited,
+                        response_time,
+                        invalid_email: email
+                            .filter(|e| !matches!(e, EmailStatus::Valid | EmailStatus::Unverified)),
+                    },
+                )
+            }
+        })
+        .buffer_unordered(client_config.max_concurrency)
+        .collect::<HashMap<&String, LinkResult>>()
+        .await;
+    cache.into_inner().unwrap().save();
+    results
+}
+
+/// Check the link with lychee client. Request that lychee can't handle
+/// is reported, not panicked on
+async fn check_request(client: &Client, request: Request) -> Status {
+    match client.check(request).await {
+        Ok(response) => response.1.status,
+        Err(error) => Status::Error(error),
+    }
+}
+
+/// Lychee client. It does not follow redirects when checkmark follows them by itself.
+/// Its HTTP client is built by checkmark, so proxy and certificates from the config are used
+fn build_client(
+    client_config: &ClientConfig,
+    headers: reqwest::header::HeaderMap,
+    follows_redirects: bool,
+) -> Client {
+    let max_redirects = match follows_redirects {
+        true => 0,
+        false => redirect::LYCHEE_MAX_REDIRECTS,
+    };
+    let reqwest_client = client_config
+        .http_client_builder()
+        .default_headers(headers.clone())
+        .redirect(redirect::policy(follows_redirects))
+        .build()
+        .unwrap();
+    ClientBuilder::builder()
+        .reqwest_client(reqwest_client)
+        .allow_insecure(client_config.allow_insecure)
+        .timeout(client_config.timeout)
+        .include_mail(client_config.check_emails)
+        .accepted(client_config.accepted_status_codes.clone())
+        .max_retries(client_config.max_retries)
+        .github_token(client_config.github_token.clone())
+        .user_agent(client_config.user_agent.clone())
+        .custom_headers(headers)
+        .max_redirects(max_redirects)
+        .build()
+        .client()
+        .unwrap()
 }
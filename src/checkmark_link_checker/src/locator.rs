@@ -0,0 +1,250 @@
+use crate::root::{is_root_relative, RootResolver};
+use common::ast::BfsIterator;
+use common::MarkDownFile;
+use lychee_lib::Uri;
+use markdown::mdast::Node;
+use markdown::unist::{Point, Position};
+use std::path::{Component, Path, PathBuf};
+
+/// Finds where links are located in the Markdown file, so issues
+/// point to the exact line and column of every occurrence
+pub struct LinkLocator<'a> {
+    file: &'a MarkDownFile,
+    /// Absolute directory of the file, relative links are resolved against it
+    dir: PathBuf,
+    resolver: &'a RootResolver,
+    /// URL and position of every link, image and definition
+    links: Vec<(String, Position)>,
+    /// Position of every HTML node, links inside of them are not parsed
+    html: Vec<Position>,
+}
+
+impl<'a> LinkLocator<'a> {
+    pub fn new(file: &'a MarkDownFile, resolver: &'a RootResolver) -> Self {
+        let (links, html) = match common::ast::parse(&file.content) {
+            Ok(ast) => (
+                BfsIterator::from(&ast)
+                    .filter_map(|node| {
+                        let url = match node {
+                            Node::Link(l) => &l.url,
+                            Node::Image(i) => &i.url,
+                            Node::Definition(d) => &d.url,
+                            _ => return None,
+                        };
+                        node.position().map(|p| (url.clone(), p.clone()))
+                    })
+                    .collect(),
+                BfsIterator::from(&ast)
+                    .filter(|node| matches!(node, Node::Html(_)))
+                    .filter_map(|node| node.position().cloned())
+                    .collect(),
+            ),
+            Err(_) => (vec![], vec![]),
+        };
+        let dir = std::path::absolute(&file.path)
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        Self {
+            file,
+            dir,
+            resolver,
+            links,
+            html,
+        }
+    }
+
+    /// Positions of all links that point to the URI. Links that are not part
+    /// of Markdown syntax are searched in the attributes of HTML nodes,
+    /// links that can not be found there have no position
+    pub fn locate(&self, uri: &Uri) -> Vec<Position> {
+        self.occurrences(uri)
+            .into_iter()
+            .map(|(_, position)| position)
+            .collect()
+    }
+
+    /// URL as it is written in the document and position of every link that points to the URI
+    pub fn occurrences(&self, uri: &Uri) -> Vec<(String, Position)> {
+        let occurrences = self
+            .links
+            .iter()
+            .filter(|(url, _)| self.is_same(url, uri))
+            .cloned()
+            .collect::<Vec<(String, Position)>>();
+        if occurrences.is_empty() {
+            self.search(uri)
+        } else {
+            occurrences
+        }
+    }
+
+    /// Whether URL from the document resolves into the URI found by the collector
+    fn is_same(&self, url: &str, uri: &Uri) -> bool {
+        // Root-relative links are resolved into remote URLs when base URL is set
+        let remote = match self.resolver.resolve(url) {
+            Some(resolved) if resolved.scheme() != "file" => Some(resolved),
+            _ => url::Url::parse(url).ok(),
+        };
+        if let Some(parsed) = remote {
+            return parsed.as_str().trim_end_matches('/') == uri.as_str().trim_end_matches('/');
+        }
+        if !uri.is_file() {
+            return false;
+        }
+        let (path, fragment) = match url.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (url, None),
+        };
+        let path = percent_encoding::percent_decode_str(path).decode_utf8_lossy();
+        let resolved = match is_root_relative(&path) {
+            true => self.resolver.root_dir().join(path.trim_start_matches('/')),
+            false => self.dir.join(path.as_ref()),
+        };
+        let is_same_path = uri
+            .url
+            .to_file_path()
+            .is_ok_and(|uri_path| clean(&uri_path) == clean(&resolved));
+        let is_same_fragment = match (fragment, uri.url.fragment()) {
+            (Some(fragment), Some(uri_fragment)) => {
+                percent_encoding::percent_decode_str(fragment).decode_utf8_lossy()
+                    == percent_encoding::percent_decode_str(uri_fragment).decode_utf8_lossy()
+            }
+            (None, None) => true,
+            // Lychee checks "foo.md" and "foo.md#x" separately
+            _ => false,
+        };
+        is_same_path && is_same_fragment
+    }
+
+    /// Search for the URI in HTML nodes of the document, e.g. `<img src="./logo.png">`.
+    /// Only whole attribute values or paths ending with the file name are matched,
+    /// so URI is not found inside of other URLs or in the text around HTML
+    fn search(&self, uri: &Uri) -> Vec<(String, Position)> {
+        let text = match uri.is_file() {
+            // Local links are relative, so only the file name is searched
+            true => uri
+                .url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .map(|name| {
+                    percent_encoding::percent_decode_str(name)
+                        .decode_utf8_lossy()
+                        .to_string()
+                })
+                .unwrap_or_default(),
+            false => crate::sanitize_uri(uri.as_str()),
+        };
+        let text = text.strip_prefix("mailto:").unwrap_or(&text);
+        if text.is_empty() {
+            return vec![];
+        }
+        let is_file = uri.is_file();
+        let mut occurrences: Vec<(String, Position)> = vec![];
+        for html in &self.html {
+            let (start, end) = (html.start.offset, html.end.offset);
+            let source = match self.file.content.get(start..end) {
+                Some(source) => source,
+                None => continue,
+            };
+            for (offset, matched) in source.match_indices(text) {
+                let before = source[..offset].chars().next_back();
+                let after = source[offset + matched.len()..].chars().next();
+                let starts_value = match is_file {
+                    // File name is the last segment of the path
+                    true => matches!(before, Some('"' | '\'' | '=' | '/')),
+                    false => matches!(before, Some('"' | '\'' | '=')),
+                };
+                let ends_value = matches!(after, Some('"' | '\'' | '#' | '?' | '/' | '>'))
+                    || after.is_some_and(char::is_whitespace);
+                if starts_value && ends_value {
+                    let offset = start + offset;
+                    occurrences.push((
+                        matched.to_string(),
+                        self.to_position(offset, offset + matched.len()),
+                    ));
+                }
+            }
+        }
+        occurrences
+    }
+
+    fn to_point(&self, offset: usize) -> Point {
+        let before = &self.file.content[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Point::new(
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+            offset,
+        )
+    }
+
+    fn to_position(&self, start: usize, end: usize) -> Position {
+        let start = self.to_point(start);
+        let end = self.to_point(end);
+        Position { start, end }
+    }
+}
+
+/// Lexically normalize the path: remove "." and resolve ".." components
+pub fn clean(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            _ => result.push(component),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_in_html_found_only_as_attribute_values() {
+        let file = MarkDownFile {
+            path: String::from("/project/README.md"),
+            content: String::from(
+                "See https://a.io/docs/x and `https://a.io`\n\n<a href=\"https://a.io\">A</a> <img src=\"./img/logo.png\">\n",
+            ),
+            issues: vec![],
+        };
+        let resolver = RootResolver::new(&common::Config::default());
+        let locator = LinkLocator::new(&file, &resolver);
+        assert_eq!(
+            vec![Position::new(3, 10, 53, 3, 22, 65)],
+            locator.locate(&Uri::try_from("https://a.io/").unwrap())
+        );
+        assert_eq!(
+            vec![Position::new(3, 46, 89, 3, 54, 97)],
+            locator.locate(&Uri::try_from("file:///project/img/logo.png").unwrap())
+        );
+        assert!(locator
+            .locate(&Uri::try_from("https://b.io/").unwrap())
+            .is_empty());
+    }
+
+    #[test]
+    fn links_with_and_without_fragment_are_different() {
+        let file = MarkDownFile {
+            path: String::from("/project/README.md"),
+            content: String::from("[A](./foo.md) [B](./foo.md#x)\n"),
+            issues: vec![],
+        };
+        let resolver = RootResolver::new(&common::Config::default());
+        let locator = LinkLocator::new(&file, &resolver);
+        assert_eq!(
+            vec![Position::new(1, 1, 0, 1, 14, 13)],
+            locator.locate(&Uri::try_from("file:///project/foo.md").unwrap())
+        );
+        assert_eq!(
+            vec![Position::new(1, 15, 14, 1, 30, 29)],
+            locator.locate(&Uri::try_from("file:///project/foo.md#x").unwrap())
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::email::{EmailStatus, EmailValidator};
+use common::ast::{try_cast_to_heading, try_cast_to_html, BfsIterator, Slugger};
+use log::debug;
+use lychee_lib::Request;
+use std::path::{Path, PathBuf};
+
+/// Result of checking a link without network access
+#[derive(Debug, Clone, PartialEq)]
+pub enum OfflineStatus {
+    /// Local file or directory exists, fragment(when any) is found
+    Ok,
+    /// External URL, or e-mail when they are not checked
+    Skipped,
+    /// Local file or directory does not exist
+    MissingPath(PathBuf),
+    /// File exists, but has no heading or HTML element that matches the fragment
+    MissingFragment(PathBuf, String),
+    /// E-mail is invalid or a placeholder, or its domain is denied
+    InvalidEmail(EmailStatus),
+}
+
+/// Check a link without touching the network. Only relative/absolute paths
+/// to files, directories and images are checked as well as fragments that
+/// point into local Markdown files. E-mails are validated without looking up their domain
+pub fn check(request: &Request, emails: Option<&EmailValidator>) -> OfflineStatus {
+    if let (true, Some(emails)) = (request.uri.is_mail(), emails) {
+        return match emails.validate_offline(request.uri.as_str()) {
+            EmailStatus::Valid => OfflineStatus::Ok,
+            status => OfflineStatus::InvalidEmail(status),
+        };
+    }
+    if !request.uri.is_file() {
+        debug!("{} is skipped in offline mode", &request.uri);
+        return OfflineStatus::Skipped;
+    }
+    let mut url = request.uri.url.clone();
+    let fragment = url.fragment().map(decode_fragment);
+    url.set_fragment(None);
+    let path = match url.to_file_path() {
+        Ok(path) => path,
+        Err(_) => return OfflineStatus::MissingPath(PathBuf::from(url.path())),
+    };
+    if !path.exists() {
+        return OfflineStatus::MissingPath(path);
+    }
+    // Link to a directory opens its index file, e.g. "docs/" opens "docs/README.md"
+    let path = match path.is_dir() {
+        true => crate::root::index_file(&path).unwrap_or(path),
+        false => path,
+    };
+    match fragment {
+        Some(fragment) if !fragment.is_empty() && is_markdown(&path) => {
+            if find_fragments(&path).contains(&fragment) {
+                OfflineStatus::Ok
+            } else {
+                OfflineStatus::MissingFragment(path, fragment)
+            }
+        }
+        _ => OfflineStatus::Ok,
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+        })
+}
+
+fn decode_fragment(fragment: &str) -> String {
+    percent_encoding::percent_decode_str(fragment)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+/// Collect fragments that can be used to link into the Markdown file:
+/// generated names of headings and "id"/"name" attributes of HTML elements
+fn find_fragments(path: &Path) -> Vec<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return vec![],
+    };
+    let ast = match common::ast::parse(&content) {
+        Ok(ast) => ast,
+        Err(_) => return vec![],
+    };
+    let mut slugger = Slugger::default();
+    let mut fragments: Vec<String> = BfsIterator::from(&ast)
+        .filter_map(|n| try_cast_to_heading(n))
+        .map(|h| slugger.slug(h).trim_start_matches('#').to_string())
+        .collect();
+    for html in BfsIterator::from(&ast).filter_map(|n| try_cast_to_html(n)) {
+        let fragment = scraper::Html::parse_fragment(&html.value);
+        for node in fragment.tree.nodes() {
+            if let Some(el) = node.value().as_element() {
+                fragments.extend(el.attr("id").map(String::from));
+                fragments.extend(el.attr("name").map(String::from));
+            }
+        }
+    }
+    fragments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_of_generated_toc_found() {
+        let source = "# Title\n\n<!-- toc -->\n<!-- tocstop -->\n\n## The `fmt` command\n\n## What's new?\n\n## Usage\n\n### Usage\n\n## *Usage*\n";
+        let content = common::toc::update(source, &common::TocConfig::default());
+        let path = std::env::temp_dir().join(format!("checkmark_toc_{}.md", std::process::id()));
+        std::fs::write(&path, &content).unwrap();
+        let fragments = find_fragments(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let links = BfsIterator::from(&common::ast::parse(&content).unwrap())
+            .filter_map(|n| common::ast::try_cast_to_link(n))
+            .map(|l| l.url.trim_start_matches('#').to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "title",
+                "the-fmt-command",
+                "whats-new",
+                "usage",
+                "usage-1",
+                "usage-2"
+            ],
+            links
+        );
+        assert!(links.iter().all(|link| fragments.contains(link)));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::client_config::ClientConfig;
+use common::RedirectsConfig;
+use log::debug;
+use lychee_lib::{ErrorKind, Status};
+use reqwest::{StatusCode, Url};
+
+/// Maximum number of redirects to follow
+const MAX_REDIRECTS: usize = 10;
+
+/// Maximum number of redirects lychee follows by itself
+pub const LYCHEE_MAX_REDIRECTS: usize = 5;
+
+/// Single redirect: status code and where it points to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hop {
+    pub status: StatusCode,
+    pub location: Url,
+}
+
+/// Redirects that happened while requesting a URL, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedirectChain {
+    pub source: Url,
+    pub hops: Vec<Hop>,
+}
+
+impl RedirectChain {
+    /// URL the source permanently moved to. Following stops at the first temporary redirect,
+    /// because its target may change. None when the first redirect is temporary
+    pub fn permanent_target(&self) -> Option<&Url> {
+        self.hops
+            .iter()
+            .take_while(|hop| is_permanent(hop.status))
+            .last()
+            .map(|hop| &hop.location)
+    }
+
+    /// URL where the chain ends
+    pub fn target(&self) -> &Url {
+        self.hops
+            .last()
+            .map(|hop| &hop.location)
+            .unwrap_or(&self.source)
+    }
+
+    /// Human-readable chain, e.g. "http://a → (301) https://a → (302) https://b"
+    pub fn describe(&self) -> String {
+        self.hops
+            .iter()
+            .fold(self.source.to_string(), |chain, hop| {
+                format!("{chain} → ({}) {}", hop.status.as_u16(), hop.location)
+            })
+    }
+
+    /// Whether the redirect is permanent and shall be reported according to the config
+    pub fn is_reportable(&self, config: &RedirectsConfig) -> bool {
+        match self.permanent_target() {
+            Some(target) => !is_ignored(&self.source, target, config),
+            None => false,
+        }
+    }
+}
+
+/// Whether redirects are followed to record their chain,
+/// which is needed both to report and to fix them
+pub fn follows(config: &RedirectsConfig) -> bool {
+    config.report || config.fix
+}
+
+/// Whether the check stopped at a redirect. Lychee client does not follow
+/// redirects when they are followed by checkmark, so such links are requested again
+pub fn is_redirected(status: &Status) -> bool {
+    match status {
+        Status::Redirected(_) | Status::Error(ErrorKind::TooManyRedirects(_)) => true,
+        Status::Error(ErrorKind::NetworkRequest(error)) => error.is_redirect(),
+        _ => false,
+    }
+}
+
+fn is_permanent(status: StatusCode) -> bool {
+    status == StatusCode::MOVED_PERMANENTLY || status == StatusCode::PERMANENT_REDIRECT
+}
+
+/// Whether redirect only adds a trailing slash or switches to https://
+/// and such redirects are ignored in the config
+fn is_ignored(source: &Url, target: &Url, config: &RedirectsConfig) -> bool {
+    let mut target = target.clone();
+    if config.ignore_https_upgrade && source.scheme() == "http" && target.scheme() == "https" {
+        let _ = target.set_scheme("http");
+    }
+    let normalize = |url: &Url| match config.ignore_trailing_slash {
+        true => url.as_str().trim_end_matches('/').to_string(),
+        false => url.to_string(),
+    };
+    normalize(source) == normalize(&target)
+}
+
+/// Redirect policy of clients that check links, same as the one of lychee client:
+/// redirects are not followed when checkmark follows them by itself
+pub fn policy(follows_redirects: bool) -> reqwest::redirect::Policy {
+    match follows_redirects {
+        true => reqwest::redirect::Policy::none(),
+        false => reqwest::redirect::Policy::limited(LYCHEE_MAX_REDIRECTS),
+    }
+}
+
+/// Client that does not follow redirects, so every one of them is recorded
+pub fn build_client(client_config: &ClientConfig) -> reqwest::Client {
+    client_config
+        .http_client_builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+}
+
+/// Request the URL and follow its redirects. Returns None when URL is
+/// not redirected or chain can not be followed
+pub async fn follow(
+    client: &reqwest::Client,
+    client_config: &ClientConfig,
+    source: &Url,
+) -> Option<RedirectChain> {
+    match send(client, client_config, source).await {
+        Ok((chain, _)) if !chain.hops.is_empty() => Some(chain),
+        Ok(_) => None,
+        Err(error) => {
+            debug!("Unable to follow redirects of {source}: {error}");
+            None
+        }
+    }
+}
+
+/// Request the URL with the client that does not follow redirects and follow
+/// them one by one. Returns the chain and the response where it stops:
+/// either not a redirect, a redirect without location or the one after the limit
+pub async fn send(
+    client: &reqwest::Client,
+    client_config: &ClientConfig,
+    source: &Url,
+) -> Result<(RedirectChain, reqwest::Response), reqwest::Error> {
+    let mut chain = RedirectChain {
+        source: source.clone(),
+        hops: vec![],
+    };
+    let mut current = source.clone();
+    loop {
+        let response = client
+            .get(current.clone())
+            .headers(client_config.headers_for(&current))
+            .send()
+            .await?;
+        if !response.status().is_redirection() || chain.hops.len() == MAX_REDIRECTS {
+            return Ok((chain, response));
+        }
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| current.join(location).ok());
+        let location = match location {
+            Some(location) => location,
+            None => return Ok((chain, response)),
+        };
+        chain.hops.push(Hop {
+            status: response.status(),
+            location: location.clone(),
+        });
+        current = location;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(source: &str, hops: &[(u16, &str)]) -> RedirectChain {
+        RedirectChain {
+            source: Url::parse(source).unwrap(),
+            hops: hops
+                .iter()
+                .map(|(status, location)| Hop {
+                    status: StatusCode::from_u16(*status).unwrap(),
+                    location: Url::parse(location).unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn permanent_target_stops_at_temporary_redirect() {
+        let redirects = chain(
+            "http://a.com/x",
+            &[
+                (301, "https://a.com/x"),
+                (308, "https://b.com/x"),
+                (302, "https://b.com/login"),
+            ],
+        );
+        assert_eq!(
+            Some(&Url::parse("https://b.com/x").unwrap()),
+            redirects.permanent_target()
+        );
+        assert_eq!(
+            None,
+            chain("https://a.com", &[(302, "https://b.com")]).permanent_target()
+        );
+    }
+
+    #[test]
+    fn trivial_redirects_ignored() {
+        let redirects = chain("http://a.com/docs", &[(301, "https://a.com/docs/")]);
+        let mut config = RedirectsConfig::default();
+        assert!(redirects.is_reportable(&config));
+        config.ignore_trailing_slash = true;
+        assert!(redirects.is_reportable(&config));
+        config.ignore_https_upgrade = true;
+        assert!(!redirects.is_reportable(&config));
+    }
+}
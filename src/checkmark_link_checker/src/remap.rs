@@ -0,0 +1,122 @@
+use crate::locator::clean;
+use common::Config;
+use lychee_lib::{Request, Uri};
+use regex::Regex;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+
+/// Replaces URLs according to the remap rules from the config
+pub struct Remapper {
+    rules: Vec<(Regex, String)>,
+    /// Relative paths in replacements are resolved against it
+    root_dir: PathBuf,
+}
+
+impl Remapper {
+    /// Rules with invalid patterns are skipped. Relative paths in replacements
+    /// are resolved against the root dir, same as root-relative links
+    pub fn new(config: &Config, root_dir: &Path) -> Self {
+        let rules = config
+            .link_checker
+            .remap
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(pattern) => Some((pattern, rule.replacement.clone())),
+                Err(error) => {
+                    log::warn!(
+                        "Skipping invalid remap pattern {:#?}: {error}",
+                        rule.pattern
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self {
+            rules,
+            root_dir: root_dir.to_path_buf(),
+        }
+    }
+
+    /// URI to check instead of the given one. First matching rule wins.
+    /// None when no rule matches
+    pub fn remap(&self, uri: &Uri) -> Option<Uri> {
+        let (pattern, replacement) = self
+            .rules
+            .iter()
+            .find(|(pattern, _)| pattern.is_match(uri.as_str()))?;
+        let remapped = pattern.replace(uri.as_str(), replacement.as_str());
+        match to_url(&remapped, &self.root_dir) {
+            Some(url) => Some(Uri { url }),
+            None => {
+                log::warn!("Unable to remap {uri} to {remapped:#?}, it is not a URL or a path");
+                None
+            }
+        }
+    }
+
+    /// Request with the remapped URI, or the same request when no rule matches
+    pub fn remap_request(&self, request: &Request) -> Request {
+        let mut request = request.clone();
+        if let Some(uri) = self.remap(&request.uri) {
+            log::debug!("Remapped {} to {uri}", request.uri);
+            request.uri = uri;
+        }
+        request
+    }
+}
+
+/// Parse replacement as a URL or, when it is not one, as a path with optional fragment
+fn to_url(replacement: &str, root_dir: &Path) -> Option<Url> {
+    if let Ok(url) = Url::parse(replacement) {
+        return Some(url);
+    }
+    let (path, fragment) = match replacement.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (replacement, None),
+    };
+    let mut url = Url::from_file_path(clean(&root_dir.join(path))).ok()?;
+    url.set_fragment(fragment);
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::RemapRule;
+
+    fn remapper(pattern: &str, replacement: &str) -> Remapper {
+        let mut config = Config::default();
+        config.link_checker.remap.push(RemapRule {
+            pattern: String::from(pattern),
+            replacement: String::from(replacement),
+        });
+        Remapper::new(&config, Path::new("/project"))
+    }
+
+    #[test]
+    fn url_remapped_to_url() {
+        let remapper = remapper(
+            r"^https://internal\.example\.com/(.*)$",
+            "http://localhost:8080/$1",
+        );
+        let uri = Uri::try_from("https://internal.example.com/api/v1").unwrap();
+        assert_eq!(
+            "http://localhost:8080/api/v1",
+            remapper.remap(&uri).unwrap().as_str()
+        );
+        let other = Uri::try_from("https://example.com/api").unwrap();
+        assert_eq!(None, remapper.remap(&other));
+    }
+
+    #[test]
+    fn url_remapped_to_local_file() {
+        let remapper = remapper(r"^https://docs\.example\.com/([^#]*)", "./docs/$1.md");
+        let uri = Uri::try_from("https://docs.example.com/guide/x#install").unwrap();
+        let remapped = remapper.remap(&uri).unwrap();
+        assert_eq!(
+            Ok(PathBuf::from("/project/docs/guide/x.md")),
+            remapped.url.to_file_path()
+        );
+        assert_eq!(Some("install"), remapped.url.fragment());
+    }
+}
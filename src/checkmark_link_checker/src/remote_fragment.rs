@@ -0,0 +1,197 @@
+use crate::client_config::ClientConfig;
+use crate::redirect::{self, RedirectChain};
+use log::debug;
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Prefix GitHub adds to ids of elements in rendered Markdown
+const GITHUB_ID_PREFIX: &str = "user-content-";
+
+/// Page fetched to check fragments that point to it
+struct Page {
+    /// Status of the response where redirects end
+    status: StatusCode,
+    /// Redirects that happened, only when they are followed by checkmark
+    redirect: Option<RedirectChain>,
+    /// Body of the page, None when it is not an HTML
+    html: Option<String>,
+}
+
+/// Result of checking a link with fragment by fetching its page
+pub struct PageCheck {
+    pub status: StatusCode,
+    pub redirect: Option<RedirectChain>,
+    /// Fragment that is not found on the page
+    pub missing_fragment: Option<String>,
+}
+
+/// Checks remote URLs with fragments by fetching their HTML pages, so the same
+/// response tells both whether the page exists and whether it has the fragment.
+/// Every page is fetched only once, no matter how many fragments point to it
+pub struct FragmentChecker<'a> {
+    client: reqwest::Client,
+    client_config: &'a ClientConfig,
+    follows_redirects: bool,
+    pages: Mutex<HashMap<String, Arc<OnceCell<Option<Page>>>>>,
+}
+
+impl<'a> FragmentChecker<'a> {
+    /// When redirects are followed by checkmark, the client does not follow
+    /// them by itself, so their chain is recorded
+    pub fn new(client_config: &'a ClientConfig, follows_redirects: bool) -> Self {
+        let client = match follows_redirects {
+            true => redirect::build_client(client_config),
+            false => client_config.http_client_builder().build().unwrap(),
+        };
+        Self {
+            client,
+            client_config,
+            follows_redirects,
+            pages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check the page of the URL and whether it has the fragment.
+    /// None when the page can't be fetched or its response is not successful,
+    /// so the link shall be checked as any other one
+    pub async fn check(&self, url: &Url) -> Option<PageCheck> {
+        let mut page_url = url.clone();
+        page_url.set_fragment(None);
+        let page = self
+            .pages
+            .lock()
+            .unwrap()
+            .entry(page_url.to_string())
+            .or_default()
+            .clone();
+        let page = page
+            .get_or_init(|| self.fetch(&page_url))
+            .await
+            .as_ref()
+            .filter(|page| page.status.is_success())?;
+        let is_github = page_url.host_str() == Some("github.com");
+        let missing_fragment = url
+            .fragment()
+            .map(decode_fragment)
+            .filter(|fragment| !fragment.is_empty() && !is_always_valid(fragment))
+            .filter(|fragment| {
+                page.html
+                    .as_ref()
+                    .is_some_and(|html| !has_fragment(html, fragment, is_github))
+            });
+        Some(PageCheck {
+            status: page.status,
+            redirect: page.redirect.clone(),
+            missing_fragment,
+        })
+    }
+
+    /// Status and body of the page, None when it can't be fetched
+    async fn fetch(&self, url: &Url) -> Option<Page> {
+        debug!("Fetching {url} to check fragments");
+        let sent = match self.follows_redirects {
+            true => redirect::send(&self.client, self.client_config, url)
+                .await
+                .map(|(chain, response)| (Some(chain).filter(|c| !c.hops.is_empty()), response)),
+            false => self
+                .client
+                .get(url.clone())
+                .headers(self.client_config.headers_for(url))
+                .send()
+                .await
+                .map(|response| (None, response)),
+        };
+        let (redirect, response) = match sent {
+            Ok(sent) => sent,
+            Err(error) => {
+                debug!("Unable to fetch {url}: {error}");
+                return None;
+            }
+        };
+        let status = response.status();
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("html"));
+        let html = match status.is_success() && is_html {
+            true => response.text().await.ok(),
+            false => None,
+        };
+        Some(Page {
+            status,
+            redirect,
+            html,
+        })
+    }
+}
+
+fn decode_fragment(fragment: &str) -> String {
+    percent_encoding::percent_decode_str(fragment)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+/// Fragments that browsers handle without an element, e.g. "#top" or text fragments
+fn is_always_valid(fragment: &str) -> bool {
+    fragment.eq_ignore_ascii_case("top") || fragment.starts_with(":~:")
+}
+
+/// Whether the page has an element with "id" or "name" that matches the fragment.
+/// GitHub prefixes ids of rendered Markdown with "user-content-" and may embed
+/// the rendered Markdown into JSON, so its pages are searched for the whole
+/// prefixed id in quotes, escaped or not, as well
+fn has_fragment(html: &str, fragment: &str, is_github: bool) -> bool {
+    let prefixed = format!("{GITHUB_ID_PREFIX}{fragment}");
+    let is_match = |value: &str| value == fragment || (is_github && value == prefixed);
+    let document = scraper::Html::parse_document(html);
+    let has_element = document.tree.nodes().any(|node| {
+        node.value().as_element().is_some_and(|el| {
+            el.attr("id").is_some_and(is_match) || el.attr("name").is_some_and(is_match)
+        })
+    });
+    let is_embedded = || {
+        html.contains(&format!("\"{prefixed}\"")) || html.contains(&format!("\\\"{prefixed}\\\""))
+    };
+    has_element || (is_github && (is_github_line(fragment) || is_embedded()))
+}
+
+/// GitHub line anchors, e.g. "L10" or "L10-L20"
+fn is_github_line(fragment: &str) -> bool {
+    let is_line = |s: &str| {
+        s.strip_prefix('L')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+    match fragment.split_once('-') {
+        Some((start, end)) => is_line(start) && is_line(end),
+        None => is_line(fragment),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragment_found_by_id_or_name() {
+        let html = r#"<html><body><h2 id="installation">Installation</h2><a name="usage"></a></body></html>"#;
+        assert!(has_fragment(html, "installation", false));
+        assert!(has_fragment(html, "usage", false));
+        assert!(!has_fragment(html, "configuration", false));
+    }
+
+    #[test]
+    fn github_fragments() {
+        let html = r#"<article><h2 id="user-content-installation">Installation</h2></article>"#;
+        assert!(has_fragment(html, "installation", true));
+        assert!(!has_fragment(html, "installation", false));
+        let embedded = r#"<script>{"richText":"<h2 id=\"user-content-usage\">"}</script>"#;
+        assert!(has_fragment(embedded, "usage", true));
+        assert!(!has_fragment(embedded, "us", true));
+        assert!(!has_fragment(html, "install", true));
+        assert!(has_fragment("", "L10-L20", true));
+        assert!(!has_fragment("", "L10-", true));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::client_config::ClientConfig;
+use crate::concurrency::HostLimiter;
+use crate::direct;
+use log::debug;
+use lychee_lib::{ErrorKind, Status, Uri};
+use reqwest::StatusCode;
+use std::future::Future;
+use std::time::{Duration, SystemTime};
+
+/// Whether server refused the request because too many requests were sent
+pub fn is_rate_limited(status: &Status) -> bool {
+    match status {
+        Status::Error(ErrorKind::NetworkRequest(error)) => {
+            error.status() == Some(StatusCode::TOO_MANY_REQUESTS)
+        }
+        _ => false,
+    }
+}
+
+/// Delay before the retry number `attempt`(starting from 0). Delay from the
+/// "Retry-After" header is used when server has sent it, otherwise base delay
+/// is doubled with every attempt. Random jitter is added, so concurrent
+/// requests to the same host are not retried at the same moment
+pub fn backoff(
+    client_config: &ClientConfig,
+    attempt: u32,
+    retry_after: Option<Duration>,
+) -> Duration {
+    let delay = retry_after
+        .unwrap_or(client_config.retry_base_delay * 2u32.saturating_pow(attempt))
+        .min(client_config.retry_max_delay);
+    delay + delay.mul_f64(fastrand::f64() / 4.0)
+}
+
+/// Parse "Retry-After" header, either seconds or HTTP date
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// Response of a direct request to the rate-limited link
+enum Attempt {
+    /// Still rate-limited, with the delay from "Retry-After" header
+    RateLimited(Option<Duration>),
+    Done(Status),
+}
+
+/// Retry rate-limited request, honouring "Retry-After" header of responses.
+/// Lychee does not expose headers, so the request is first re-issued directly
+/// to read the header of the rate-limited response. Successful response of a
+/// direct request is the verdict, otherwise the link is checked again with `check`,
+/// so the verdict is the same as for links that are not rate-limited.
+/// Returns None when request is still rate-limited after all retries,
+/// right away when retries are disabled with `max_retries = 0`
+pub async fn retry<F, R>(
+    client: &reqwest::Client,
+    client_config: &ClientConfig,
+    limiter: &HostLimiter,
+    uri: &Uri,
+    check: F,
+) -> Option<Status>
+where
+    F: Fn() -> R,
+    R: Future<Output = Status>,
+{
+    if client_config.max_retries == 0 {
+        return None;
+    }
+    let mut attempt = send(client, client_config, uri).await;
+    for retry in 0..client_config.max_retries as u32 {
+        let retry_after = match &attempt {
+            Attempt::RateLimited(retry_after) => *retry_after,
+            Attempt::Done(_) => break,
+        };
+        let delay = backoff(client_config, retry, retry_after);
+        debug!("{uri} is rate-limited, retrying in {delay:?}");
+        limiter.pause(uri, delay);
+        limiter.wait(uri).await;
+        attempt = send(client, client_config, uri).await;
+    }
+    match attempt {
+        Attempt::RateLimited(_) => None,
+        Attempt::Done(status) if status.is_success() => Some(status),
+        Attempt::Done(_) => Some(check().await),
+    }
+}
+
+/// Send request to the link directly, reading "Retry-After" header when it is rate-limited
+async fn send(client: &reqwest::Client, client_config: &ClientConfig, uri: &Uri) -> Attempt {
+    let response = match direct::send(client, client_config, uri).await {
+        Ok(response) => response,
+        Err(status) => return Attempt::Done(status),
+    };
+    if response.status() != StatusCode::TOO_MANY_REQUESTS {
+        return Attempt::Done(direct::to_status(response, client_config));
+    }
+    Attempt::RateLimited(
+        response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, SystemTime::now())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_parsed_from_seconds_and_date() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            Some(Duration::from_secs(120)),
+            parse_retry_after("120", now)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now)
+        );
+        assert_eq!(
+            Some(Duration::ZERO),
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now)
+        );
+        assert_eq!(None, parse_retry_after("soon", now));
+    }
+
+    #[tokio::test]
+    async fn rate_limited_right_away_when_retries_disabled() {
+        let client_config = ClientConfig {
+            max_retries: 0,
+            ..ClientConfig::from_checkmark_config(&common::Config::default())
+        };
+        let limiter = HostLimiter::new(1, Duration::ZERO);
+        let uri = Uri::try_from("http://127.0.0.1:9/").unwrap();
+        let status = retry(
+            &reqwest::Client::new(),
+            &client_config,
+            &limiter,
+            &uri,
+            || async { unreachable!("link is not checked again") },
+        )
+        .await;
+        assert!(status.is_none());
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_is_capped() {
+        let client_config = ClientConfig::from_checkmark_config(&common::Config::default());
+        for (attempt, retry_after, expected) in [
+            (0, None, 1),
+            (1, None, 2),
+            (2, None, 4),
+            (10, None, 60),
+            (0, Some(Duration::from_secs(5)), 5),
+            (0, Some(Duration::from_secs(3600)), 60),
+        ] {
+            let delay = backoff(&client_config, attempt, retry_after);
+            let expected = Duration::from_secs(expected);
+            assert!(delay >= expected && delay <= expected.mul_f64(1.25));
+        }
+    }
+}
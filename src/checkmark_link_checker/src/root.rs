@@ -0,0 +1,123 @@
+use common::Config;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+
+/// Files that are opened when a link points to a directory, in order of preference
+const INDEX_FILES: [&str; 3] = ["README.md", "readme.md", "index.md"];
+
+/// Resolves root-relative links(e.g. "/docs/api.md") against the root dir
+/// or the base URL from the config instead of the filesystem root
+pub struct RootResolver {
+    root_dir: PathBuf,
+    base_url: Option<Url>,
+}
+
+impl RootResolver {
+    /// Root dir is normally set to the project root by the caller, see `project_root_dir`.
+    /// When it is not set, the current dir is treated as the project root
+    pub fn new(config: &Config) -> Self {
+        let root_dir = match &config.link_checker.root_dir {
+            Some(root_dir) => PathBuf::from(root_dir),
+            None => project_root_dir(&std::env::current_dir().unwrap_or_default()),
+        };
+        let base_url = config
+            .link_checker
+            .base_url
+            .as_ref()
+            .and_then(|base_url| match Url::parse(base_url) {
+                Ok(url) => Some(url),
+                Err(error) => {
+                    log::warn!("Ignoring invalid base URL {base_url:#?}: {error}");
+                    None
+                }
+            });
+        Self {
+            root_dir: std::path::absolute(&root_dir).unwrap_or(root_dir),
+            base_url,
+        }
+    }
+
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
+    }
+
+    /// URL of the root-relative link. None when the link is not root-relative
+    pub fn resolve(&self, link: &str) -> Option<Url> {
+        if !is_root_relative(link) {
+            return None;
+        }
+        if let Some(base_url) = &self.base_url {
+            // Base URL is a directory, so its path is kept
+            let mut base_url = base_url.clone();
+            if !base_url.path().ends_with('/') {
+                base_url.set_path(&format!("{}/", base_url.path()));
+            }
+            return base_url.join(link.trim_start_matches('/')).ok();
+        }
+        let (path, fragment) = match link.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (link, None),
+        };
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+        let path = percent_encoding::percent_decode_str(path).decode_utf8_lossy();
+        let mut url = Url::from_file_path(self.root_dir.join(path.trim_start_matches('/'))).ok()?;
+        url.set_fragment(fragment);
+        Some(url)
+    }
+}
+
+/// Link relative to the root starts with a single slash, two slashes start a scheme-relative URL
+pub fn is_root_relative(link: &str) -> bool {
+    link.starts_with('/') && !link.starts_with("//")
+}
+
+/// Index file of the directory, e.g. "docs/README.md" for "docs/"
+pub fn index_file(dir: &Path) -> Option<PathBuf> {
+    INDEX_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Dir against which root-relative links of the project are resolved:
+/// the project dir itself, or the root of the Git repository of a single file
+/// (its dir when it is not in a repository)
+pub fn project_root_dir(project_root: &Path) -> PathBuf {
+    let project_root = std::path::absolute(project_root).unwrap_or(project_root.to_path_buf());
+    if project_root.is_dir() {
+        return project_root;
+    }
+    let dir = project_root.parent().unwrap_or(&project_root);
+    find_git_root(dir).unwrap_or(dir.to_path_buf())
+}
+
+fn find_git_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_relative_links_resolved_against_root_dir_or_base_url() {
+        let mut config = Config::default();
+        config.link_checker.root_dir = Some(String::from("/project"));
+        let resolver = RootResolver::new(&config);
+        assert_eq!(
+            "file:///project/docs/api.md#usage",
+            resolver.resolve("/docs/api.md#usage").unwrap().as_str()
+        );
+        assert_eq!(None, resolver.resolve("docs/api.md"));
+        assert_eq!(None, resolver.resolve("//example.com/docs"));
+
+        config.link_checker.base_url = Some(String::from("https://example.com/project"));
+        let resolver = RootResolver::new(&config);
+        assert_eq!(
+            "https://example.com/project/docs/api.md#usage",
+            resolver.resolve("/docs/api.md#usage").unwrap().as_str()
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+
+#[rule(
+    requirement = "Table of contents should be up to date",
+    rationale = "Table of contents between \"<!-- toc -->\" and \"<!-- tocstop -->\" markers is meant to reflect document's headings. Hand-maintained table of contents drifts away from the document when headings are added, renamed or removed, which leaves readers with missing entries and broken links",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm001(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = vec![];
+    if let Some(location) = common::toc::locate(ast) {
+        let expected = common::toc::collect(ast, &config.style.toc);
+        let toc = common::toc::render(&expected, &config.style.toc);
+        if !common::toc::is_up_to_date(&file.content, &location, &toc) {
+            violations.push(
+                violation_builder()
+                    .position(&Some(location.position))
+                    .push_fix(&format!("Replace table of contents with:\n\n{toc}\n"))
+                    .build(),
+            );
+        }
+    }
+    violations
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Stale table of contents")
+        .assertion("Expected table of contents to list all document's headings, got the one that differs from them")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rule_test(
+        markdown = "# Title\n\n<!-- toc -->\n\n- [Title](#title)\n  - [Old](#old)\n\n<!-- tocstop -->\n\n## New\n"
+    )]
+    fn detect_stale_toc(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(
+            vec![violation_builder()
+                .position(&Some(Position::new(3, 1, 9, 8, 17, 74)))
+                .push_fix("Replace table of contents with:\n\n- [Title](#title)\n  - [New](#new)\n")
+                .build()],
+            CM001.check(ast, file, config)
+        );
+    }
+
+    #[rule_test(
+        markdown = "# Title\n\n<!-- toc -->\n\n- [Title](#title)\n  - [New](#new)\n\n<!-- tocstop -->\n\n## New\n"
+    )]
+    fn do_not_complain_about_up_to_date_toc(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM001.check(ast, file, config));
+    }
+
+    #[rule_test(
+        markdown = "# Title\n\n<!-- toc -->\n\n- [Title](#title)\n- [New](#new)\n\n<!-- tocstop -->\n\n## New\n"
+    )]
+    fn detect_toc_with_wrong_nesting(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(1, CM001.check(ast, file, config).len());
+    }
+
+    #[rule_test(markdown = "# Title\n\n- [Old](#old)\n\n## New\n")]
+    fn do_not_complain_without_markers(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM001.check(ast, file, config));
+    }
+}
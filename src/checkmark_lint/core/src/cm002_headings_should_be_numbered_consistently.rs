@@ -0,0 +1,107 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+
+#[rule(
+    requirement = "Heading numbers should be consistent",
+    rationale = "Numbered sections(e.g. \"2.3.1 Error handling\") let readers refer to parts of a specification. Numbers maintained by hand get out of order when sections are added, removed or moved around, so references start pointing to the wrong section",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm002(ast: &Node, _: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::heading_numbering::check(ast, &config.style.heading_numbering)
+        .iter()
+        .map(|heading| {
+            let (assertion, fix) = match (&heading.actual, &heading.expected) {
+                (Some(actual), Some(expected)) => (
+                    format!("Expected heading to be numbered as \"{expected}\", got \"{actual}\""),
+                    format!("Change heading number to \"{expected}\""),
+                ),
+                (None, Some(expected)) => (
+                    format!("Expected heading to be numbered as \"{expected}\", got no number"),
+                    format!("Add number \"{expected}\" to the heading"),
+                ),
+                (Some(actual), None) => (
+                    format!("Expected heading without a number, got \"{actual}\""),
+                    format!("Remove number \"{actual}\" from the heading"),
+                ),
+                (None, None) => unreachable!("Correctly numbered headings are not reported"),
+            };
+            ViolationBuilder::default()
+                .message("Incorrect heading number")
+                .assertion(&assertion)
+                .position(&Some(heading.position.clone()))
+                .push_fix(&fix)
+                .build()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_config(style: common::HeadingNumberingStyle) -> Config {
+        Config {
+            style: common::StyleConfig {
+                heading_numbering: common::HeadingNumberingConfig {
+                    style,
+                    start_depth: 2,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "# Spec\n\n## 1 Intro\n\n## 3 Errors\n\n### Handling\n")]
+    fn detect_incorrect_numbers(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![
+                ViolationBuilder::default()
+                    .message("Incorrect heading number")
+                    .assertion("Expected heading to be numbered as \"2\", got \"3\"")
+                    .position(&Some(Position::new(5, 1, 20, 5, 12, 31)))
+                    .push_fix("Change heading number to \"2\"")
+                    .build(),
+                ViolationBuilder::default()
+                    .message("Incorrect heading number")
+                    .assertion("Expected heading to be numbered as \"2.1\", got no number")
+                    .position(&Some(Position::new(7, 1, 33, 7, 13, 45)))
+                    .push_fix("Add number \"2.1\" to the heading")
+                    .build(),
+            ],
+            CM002.check(
+                ast,
+                file,
+                &numbered_config(common::HeadingNumberingStyle::Numbered)
+            )
+        );
+    }
+
+    #[rule_test(markdown = "# Spec\n\n## 1 Intro\n\n## Errors\n")]
+    fn detect_numbers_that_should_be_stripped(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![ViolationBuilder::default()
+                .message("Incorrect heading number")
+                .assertion("Expected heading without a number, got \"1\"")
+                .position(&Some(Position::new(3, 1, 8, 3, 11, 18)))
+                .push_fix("Remove number \"1\" from the heading")
+                .build()],
+            CM002.check(
+                ast,
+                file,
+                &numbered_config(common::HeadingNumberingStyle::Unnumbered)
+            )
+        );
+    }
+
+    #[rule_test(markdown = "# Spec\n\n## 7 Intro\n\n## Errors\n")]
+    fn do_not_complain_when_numbering_is_preserved(
+        ast: &Node,
+        file: &MarkDownFile,
+        config: &Config,
+    ) {
+        assert_eq!(Vec::<Violation>::new(), CM002.check(ast, file, config));
+    }
+}
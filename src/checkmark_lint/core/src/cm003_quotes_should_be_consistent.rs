@@ -0,0 +1,78 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+use common::typography::Check;
+
+#[rule(
+    requirement = "Quotes should be consistent",
+    rationale = "Mixing straight and curly quotes looks sloppy in the rendered document. Curly quotes are preferred in published prose while straight ones are easier to type and search for, either way the document should stick to one of them",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm003(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::typography::check(ast, &file.content, Check::Quotes, &config.fmt.typography)
+        .into_iter()
+        .map(|issue| {
+            violation_builder()
+                .position(&issue.position)
+                .push_fix(&issue.fix())
+                .build()
+        })
+        .collect()
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Inconsistent quotes")
+        .assertion("Expected quotes to match configured style, got the ones that do not")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            fmt: common::FmtConfig {
+                typography: common::TypographyConfig {
+                    quotes: common::QuotesStyle::Curly,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "Say \"hello\"\n")]
+    fn detect(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![
+                violation_builder()
+                    .position(&Some(Position::new(1, 5, 4, 1, 6, 5)))
+                    .push_fix("Replace \"\\\"\" with \"“\"")
+                    .build(),
+                violation_builder()
+                    .position(&Some(Position::new(1, 11, 10, 1, 12, 11)))
+                    .push_fix("Replace \"\\\"\" with \"”\"")
+                    .build()
+            ],
+            CM003.check(ast, file, &config())
+        );
+    }
+
+    #[rule_test(markdown = "Say \\\"hello\\\"\n")]
+    fn do_not_complain_about_escaped_quotes(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM003.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "Say “hello”\n")]
+    fn do_not_complain_about_typographic_text(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM003.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "Say \"hello\"\n")]
+    fn do_not_complain_when_disabled(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM003.check(ast, file, config));
+    }
+}
@@ -0,0 +1,73 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+use common::typography::Check;
+
+#[rule(
+    requirement = "Dashes should be typographic",
+    rationale = "Double and triple hyphens are a typewriter substitute for en and em dashes. Typographic dashes read better and do not depend on the rendering engine to convert them",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm004(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::typography::check(ast, &file.content, Check::Dashes, &config.fmt.typography)
+        .into_iter()
+        .map(|issue| {
+            violation_builder()
+                .position(&issue.position)
+                .push_fix(&issue.fix())
+                .build()
+        })
+        .collect()
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Hyphens used as a dash")
+        .assertion("Expected en(–) or em(—) dash, got hyphens")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            fmt: common::FmtConfig {
+                typography: common::TypographyConfig {
+                    dashes: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "Pages 1--2 --- or use --help\n")]
+    fn detect(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![
+                violation_builder()
+                    .position(&Some(Position::new(1, 8, 7, 1, 10, 9)))
+                    .push_fix("Replace \"--\" with \"–\"")
+                    .build(),
+                violation_builder()
+                    .position(&Some(Position::new(1, 12, 11, 1, 15, 14)))
+                    .push_fix("Replace \"---\" with \"—\"")
+                    .build()
+            ],
+            CM004.check(ast, file, &config())
+        );
+    }
+
+    #[rule_test(markdown = "Pages 1–2 — or use --help\n")]
+    fn do_not_complain_about_typographic_text(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM004.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "Pages 1--2 --- or use --help\n")]
+    fn do_not_complain_when_disabled(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM004.check(ast, file, config));
+    }
+}
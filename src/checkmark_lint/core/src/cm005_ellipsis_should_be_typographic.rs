@@ -0,0 +1,67 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+use common::typography::Check;
+
+#[rule(
+    requirement = "Ellipsis should be typographic",
+    rationale = "Three periods are a typewriter substitute for ellipsis. Ellipsis character keeps dots together when the line wraps and has the spacing designed for it",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm005(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::typography::check(ast, &file.content, Check::Ellipsis, &config.fmt.typography)
+        .into_iter()
+        .map(|issue| {
+            violation_builder()
+                .position(&issue.position)
+                .push_fix(&issue.fix())
+                .build()
+        })
+        .collect()
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Periods used as an ellipsis")
+        .assertion("Expected ellipsis(…), got three periods")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            fmt: common::FmtConfig {
+                typography: common::TypographyConfig {
+                    ellipsis: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "Wait...\n")]
+    fn detect(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![violation_builder()
+                .position(&Some(Position::new(1, 5, 4, 1, 8, 7)))
+                .push_fix("Replace \"...\" with \"…\"")
+                .build()],
+            CM005.check(ast, file, &config())
+        );
+    }
+
+    #[rule_test(markdown = "Wait…\n")]
+    fn do_not_complain_about_typographic_text(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM005.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "Wait...\n")]
+    fn do_not_complain_when_disabled(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM005.check(ast, file, config));
+    }
+}
@@ -0,0 +1,72 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+use common::typography::Check;
+
+#[rule(
+    requirement = "Units should be separated from numbers by a non-breaking space",
+    rationale = "Regular space lets the line break between a number and its unit, leaving \"10\" at the end of one line and \"kg\" at the beginning of the next one. Non-breaking space keeps them together",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm006(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::typography::check(
+        ast,
+        &file.content,
+        Check::UnitSpaces,
+        &config.fmt.typography,
+    )
+    .into_iter()
+    .map(|issue| {
+        violation_builder()
+            .position(&issue.position)
+            .push_fix(&issue.fix())
+            .build()
+    })
+    .collect()
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Breaking space before a unit")
+        .assertion("Expected non-breaking space between a number and a unit, got regular space")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            fmt: common::FmtConfig {
+                typography: common::TypographyConfig {
+                    nbsp_before_units: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "It weighs 10 kg\n")]
+    fn detect(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![violation_builder()
+                .position(&Some(Position::new(1, 13, 12, 1, 14, 13)))
+                .push_fix("Replace \" \" with \"\\u{a0}\"")
+                .build()],
+            CM006.check(ast, file, &config())
+        );
+    }
+
+    #[rule_test(markdown = "It weighs 10\u{a0}kg and has 10 kids\n")]
+    fn do_not_complain_about_typographic_text(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM006.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "It weighs 10 kg\n")]
+    fn do_not_complain_when_disabled(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM006.check(ast, file, config));
+    }
+}
@@ -0,0 +1,72 @@
+use checkmark_lint_common::*;
+use checkmark_lint_macro::*;
+use common::typography::Check;
+
+#[rule(
+    requirement = "Sentences should be separated by a single space",
+    rationale = "Two spaces after a period is a typewriter habit. Markdown engines collapse them anyway, so they only make the source inconsistent and confuse diffs",
+    documentation = "https://github.com/vvvar/checkmark#fmt",
+    additional_links = [],
+    is_fmt_fixable = true,
+)]
+fn cm007(ast: &Node, file: &MarkDownFile, config: &Config) -> Vec<Violation> {
+    common::typography::check(
+        ast,
+        &file.content,
+        Check::SentenceSpaces,
+        &config.fmt.typography,
+    )
+    .into_iter()
+    .map(|issue| {
+        violation_builder()
+            .position(&issue.position)
+            .push_fix(&issue.fix())
+            .build()
+    })
+    .collect()
+}
+
+fn violation_builder() -> ViolationBuilder {
+    ViolationBuilder::default()
+        .message("Multiple spaces after a sentence")
+        .assertion("Expected single space after the end of a sentence, got multiple")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            fmt: common::FmtConfig {
+                typography: common::TypographyConfig {
+                    single_space_after_period: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rule_test(markdown = "One.  Two\n")]
+    fn detect(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(
+            vec![violation_builder()
+                .position(&Some(Position::new(1, 5, 4, 1, 7, 6)))
+                .push_fix("Replace \"  \" with \" \"")
+                .build()],
+            CM007.check(ast, file, &config())
+        );
+    }
+
+    #[rule_test(markdown = "One. Two  three\n")]
+    fn do_not_complain_about_typographic_text(ast: &Node, file: &MarkDownFile, _: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM007.check(ast, file, &config()));
+    }
+
+    #[rule_test(markdown = "One.  Two\n")]
+    fn do_not_complain_when_disabled(ast: &Node, file: &MarkDownFile, config: &Config) {
+        assert_eq!(Vec::<Violation>::new(), CM007.check(ast, file, config));
+    }
+}
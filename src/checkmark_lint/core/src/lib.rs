@@ -1,3 +1,24 @@
+mod cm001_table_of_contents_should_be_up_to_date;
+use cm001_table_of_contents_should_be_up_to_date::CM001;
+
+mod cm002_headings_should_be_numbered_consistently;
+use cm002_headings_should_be_numbered_consistently::CM002;
+
+mod cm003_quotes_should_be_consistent;
+use cm003_quotes_should_be_consistent::CM003;
+
+mod cm004_dashes_should_be_typographic;
+use cm004_dashes_should_be_typographic::CM004;
+
+mod cm005_ellipsis_should_be_typographic;
+use cm005_ellipsis_should_be_typographic::CM005;
+
+mod cm006_units_should_be_separated_by_non_breaking_space;
+use cm006_units_should_be_separated_by_non_breaking_space::CM006;
+
+mod cm007_sentences_should_be_separated_by_single_space;
+use cm007_sentences_should_be_separated_by_single_space::CM007;
+
 mod md001_heading_level_should_increment_by_one_level_at_time;
 use md001_heading_level_should_increment_by_one_level_at_time::MD001;
 
@@ -173,6 +194,13 @@ pub fn lint(file: &MarkDownFile, config: &Config) -> Vec<CheckIssue> {
         Box::new(MD033),
         Box::new(MD046),
         Box::new(MD051),
+        Box::new(CM001),
+        Box::new(CM002),
+        Box::new(CM003),
+        Box::new(CM004),
+        Box::new(CM005),
+        Box::new(CM006),
+        Box::new(CM007),
     ];
     rules
         .into_par_iter()
@@ -1,6 +1,6 @@
 use checkmark_lint_common::*;
 use checkmark_lint_macro::*;
-use common::ast::{try_cast_to_heading, try_cast_to_html, try_cast_to_link, BfsIterator};
+use common::ast::{try_cast_to_heading, try_cast_to_html, try_cast_to_link, BfsIterator, Slugger};
 
 use scraper::{Html, Node as HtmlNode};
 
@@ -43,26 +43,6 @@ fn extract_headings(ast: &Node) -> Vec<&Heading> {
         .collect::<Vec<&Heading>>()
 }
 
-/// Takes heading and returns fragment link of it.
-/// Link element that want to jump to this header
-/// should use this fragment.
-fn heading_to_fragment(heading: &Heading) -> String {
-    let mut text = "".to_string();
-    for node in &heading.children {
-        if let Node::Text(t) = node {
-            text = format!("{}{}", &text, &t.value);
-        }
-    }
-    text = format!(
-        "#{}",
-        &text
-            .to_lowercase()
-            .replace([',', '.', '+', '&'], "")
-            .replace(' ', "-")
-    );
-    text
-}
-
 /// Get all HTML links(<a/>).
 /// At least one of them shall contain an anchor.
 fn extract_html_elements(ast: &Node) -> Vec<HtmlNode> {
@@ -85,12 +65,14 @@ fn find_violations(
     headings: &[&Heading],
     html_els: &[scraper::Node],
 ) -> Vec<Violation> {
+    let mut slugger = Slugger::default();
+    let fragments = headings
+        .iter()
+        .map(|heading| slugger.slug(heading))
+        .collect::<Vec<String>>();
     // Does link fragment point to a header?
-    let does_fragment_points_to_header = |anchor: &Link| {
-        headings
-            .iter()
-            .any(|heading| anchor.url.eq(&heading_to_fragment(heading)))
-    };
+    let does_fragment_points_to_header =
+        |anchor: &Link| fragments.iter().any(|fragment| anchor.url.eq(fragment));
     // Does anchor points to any other anchor in HTML <a id="#anchor"/>?
     let does_fragment_points_to_html = |link: &Link| {
         html_els.iter().any(|html_el| {
@@ -138,6 +120,17 @@ mod tests {
         assert_eq!(Vec::<Violation>::new(), MD051.check(ast, file, config));
     }
 
+    #[rule_test(
+        markdown = "# What's new\n\n## The `fmt` command\n\n## Usage\n\n## Usage\n\n[New](#whats-new) [Fmt](#the-fmt-command) [Usage](#usage-1)"
+    )]
+    fn do_not_complain_about_github_fragments_of_headings(
+        ast: &Node,
+        file: &MarkDownFile,
+        config: &Config,
+    ) {
+        assert_eq!(Vec::<Violation>::new(), MD051.check(ast, file, config));
+    }
+
     #[rule_test(markdown = "# Seek & Destroy\n\n[Song](#seek--destroy)")]
     fn handle_more_complex_case_when_heading_name_is_transformed(
         ast: &Node,
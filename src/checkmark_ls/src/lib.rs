@@ -23,10 +23,11 @@ fn tmp_dir(uri: &str) -> std::path::PathBuf {
 /// Path could be:
 ///     1. path to a file - will just add this file to the list
 ///     2. path to a dir - will lookup all markdown files in this ir
-///     3. remote URL
+///     3. remote URL, downloaded with proxy and TLS settings from the network config
 pub async fn ls(
     path: &str,
     exclude: &Vec<String>,
+    network: &common::NetworkConfig,
     tui: &Arc<Mutex<common::tui::CheckProgressTUI>>,
 ) -> Vec<common::MarkDownFile> {
     log::debug!("Listing Markdown files in: {:#?}", &path);
@@ -89,7 +90,13 @@ pub async fn ls(
     } else if is_url::is_url(&input_path) {
         log::debug!("Path is a plain URL, downloading as single file into tmp dir");
 
-        let response = reqwest::get(&input_path).await.unwrap();
+        let response = common::network::client_builder(network)
+            .build()
+            .unwrap()
+            .get(&input_path)
+            .send()
+            .await
+            .unwrap();
 
         let tmp_file_path = tmp_dir(&input_path).join(format!(
             "{}.md",
@@ -176,18 +183,21 @@ pub async fn ls(
     }
 
     // Filter files by exclude patterns
-    markdown_files.retain(|markdown_file| {
-        let unix_style_file_path = Path::new(&markdown_file.path)
-            .to_slash()
-            .unwrap_or(Cow::from(markdown_file.path.clone()));
-        for exclude_pattern in exclude {
-            if WildMatch::new(exclude_pattern).matches(&unix_style_file_path) {
-                log::debug!("Ignoring {:#?}", &markdown_file.path);
-                return false;
-            }
-        }
-        true
-    });
+    markdown_files.retain(|markdown_file| !is_excluded(&markdown_file.path, exclude));
 
     markdown_files
 }
+
+/// Returns true when the path matches any of exclude patterns
+pub fn is_excluded(path: &str, exclude: &[String]) -> bool {
+    let unix_style_file_path = Path::new(path)
+        .to_slash()
+        .unwrap_or(Cow::from(path.to_owned()));
+    for exclude_pattern in exclude {
+        if WildMatch::new(exclude_pattern).matches(&unix_style_file_path) {
+            log::debug!("Ignoring {:#?}", &path);
+            return true;
+        }
+    }
+    false
+}
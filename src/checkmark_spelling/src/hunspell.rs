@@ -0,0 +1,355 @@
+//! Minimal reader of Hunspell dictionaries. SymSpell has no idea of affixes,
+//! so every stem of the ".dic" file is expanded with prefixes and suffixes
+//! of the ".aff" file into the full list of word forms.
+//! Compounding rules are not supported.
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// How flags are written in the ".dic" file and affix rules
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagType {
+    /// Single character, default
+    Char,
+    /// Two characters, "FLAG long"
+    Long,
+    /// Comma-separated numbers, "FLAG num"
+    Num,
+}
+
+/// Single character of the affix condition
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionChar {
+    Any,
+    Is(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl ConditionChar {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionChar::Any => true,
+            ConditionChar::Is(expected) => *expected == c,
+            ConditionChar::OneOf(chars) => chars.contains(&c),
+            ConditionChar::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AffixRule {
+    strip: String,
+    append: String,
+    /// Flags of affixes that can be added on top of this one
+    continuation: Vec<String>,
+    condition: Vec<ConditionChar>,
+}
+
+impl AffixRule {
+    fn can_suffix(&self, word: &str) -> bool {
+        let chars = word.chars().collect::<Vec<char>>();
+        word.ends_with(&self.strip)
+            && chars.len() > self.strip.chars().count()
+            && chars.len() >= self.condition.len()
+            && chars[chars.len() - self.condition.len()..]
+                .iter()
+                .zip(&self.condition)
+                .all(|(c, condition)| condition.matches(*c))
+    }
+
+    fn can_prefix(&self, word: &str) -> bool {
+        let chars = word.chars().collect::<Vec<char>>();
+        word.starts_with(&self.strip)
+            && chars.len() > self.strip.chars().count()
+            && chars.len() >= self.condition.len()
+            && chars
+                .iter()
+                .zip(&self.condition)
+                .all(|(c, condition)| condition.matches(*c))
+    }
+
+    fn suffix(&self, word: &str) -> String {
+        format!("{}{}", &word[..word.len() - self.strip.len()], self.append)
+    }
+
+    fn prefix(&self, word: &str) -> String {
+        format!("{}{}", self.append, &word[self.strip.len()..])
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct AffixGroup {
+    /// Prefixes and suffixes of the group can be combined
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Debug, Default)]
+struct Affixes {
+    flag_type: Option<FlagType>,
+    prefixes: HashMap<String, AffixGroup>,
+    suffixes: HashMap<String, AffixGroup>,
+    /// Flag aliases("AF"), referenced by their number in the ".dic" file
+    aliases: Vec<String>,
+    need_affix: Option<String>,
+    forbidden: Vec<String>,
+}
+
+impl Affixes {
+    fn parse(content: &str) -> Self {
+        let mut affixes = Affixes::default();
+        // First "AF" line is the number of aliases
+        let mut has_aliases = false;
+        for line in content.lines() {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => affixes.flag_type = Some(FlagType::Long),
+                ["FLAG", "num", ..] => affixes.flag_type = Some(FlagType::Num),
+                ["AF", flags, ..] if has_aliases => affixes.aliases.push(flags.to_string()),
+                ["AF", ..] => has_aliases = true,
+                ["NEEDAFFIX", flag, ..] => affixes.need_affix = Some(flag.to_string()),
+                ["FORBIDDENWORD" | "ONLYINCOMPOUND", flag, ..] => {
+                    affixes.forbidden.push(flag.to_string())
+                }
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    let group = AffixGroup {
+                        cross_product: *cross_product == "Y",
+                        rules: vec![],
+                    };
+                    affixes.groups(kind).insert(flag.to_string(), group);
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, append, rest @ ..] => {
+                    let (append, continuation) = append.split_once('/').unwrap_or((append, ""));
+                    let rule = AffixRule {
+                        strip: none_if_zero(strip),
+                        append: none_if_zero(append),
+                        continuation: affixes.flags(continuation),
+                        condition: parse_condition(rest.first().unwrap_or(&".")),
+                    };
+                    if let Some(group) = affixes.groups(kind).get_mut(*flag) {
+                        group.rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        affixes
+    }
+
+    fn groups(&mut self, kind: &str) -> &mut HashMap<String, AffixGroup> {
+        match kind {
+            "PFX" => &mut self.prefixes,
+            _ => &mut self.suffixes,
+        }
+    }
+
+    fn flags(&self, flags: &str) -> Vec<String> {
+        let flags = match flags.parse::<usize>() {
+            Ok(index) if !self.aliases.is_empty() => index
+                .checked_sub(1)
+                .and_then(|index| self.aliases.get(index))
+                .map_or("", |alias| alias),
+            _ => flags,
+        };
+        match self.flag_type.unwrap_or(FlagType::Char) {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(2)
+                .map(|chunk| chunk.iter().collect())
+                .collect(),
+            FlagType::Num => flags.split(',').map(String::from).collect(),
+        }
+    }
+
+    /// Stem with all its prefixed and suffixed forms
+    fn expand(&self, stem: &str, flags: &[String]) -> Vec<String> {
+        if flags.iter().any(|flag| self.forbidden.contains(flag)) {
+            return vec![];
+        }
+        let mut words: Vec<String> = vec![];
+        if !flags
+            .iter()
+            .any(|flag| Some(flag) == self.need_affix.as_ref())
+        {
+            words.push(stem.to_string());
+        }
+        // Suffixed forms that prefixes can be added to
+        let mut crossable: Vec<String> = vec![];
+        for group in flags.iter().filter_map(|flag| self.suffixes.get(flag)) {
+            for rule in group.rules.iter().filter(|rule| rule.can_suffix(stem)) {
+                let word = rule.suffix(stem);
+                for next in rule
+                    .continuation
+                    .iter()
+                    .filter_map(|flag| self.suffixes.get(flag))
+                {
+                    words.extend(
+                        next.rules
+                            .iter()
+                            .filter(|next| next.can_suffix(&word))
+                            .map(|next| next.suffix(&word)),
+                    );
+                }
+                if group.cross_product {
+                    crossable.push(word.clone());
+                }
+                words.push(word);
+            }
+        }
+        for group in flags.iter().filter_map(|flag| self.prefixes.get(flag)) {
+            for rule in group.rules.iter() {
+                if rule.can_prefix(stem) {
+                    words.push(rule.prefix(stem));
+                }
+                if group.cross_product {
+                    words.extend(
+                        crossable
+                            .iter()
+                            .filter(|word| rule.can_prefix(word))
+                            .map(|word| rule.prefix(word)),
+                    );
+                }
+            }
+        }
+        words
+    }
+}
+
+fn none_if_zero(value: &str) -> String {
+    match value {
+        "0" => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Condition is a simplified regular expression, e.g. "[^aeiou]y" or "."
+fn parse_condition(condition: &str) -> Vec<ConditionChar> {
+    if condition == "." {
+        return vec![];
+    }
+    let mut parsed: Vec<ConditionChar> = vec![];
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parsed.push(ConditionChar::Any),
+            '[' => {
+                let set = chars.by_ref().take_while(|c| *c != ']').collect::<String>();
+                match set.strip_prefix('^') {
+                    Some(set) => parsed.push(ConditionChar::NoneOf(set.chars().collect())),
+                    None => parsed.push(ConditionChar::OneOf(set.chars().collect())),
+                }
+            }
+            c => parsed.push(ConditionChar::Is(c)),
+        }
+    }
+    parsed
+}
+
+/// Dictionaries are either UTF-8 or use the legacy encoding from the "SET" option
+fn decode(bytes: &[u8], encoding: &str) -> String {
+    match encoding {
+        "ISO8859-1" | "ISO-8859-1" => bytes.iter().map(|byte| *byte as char).collect(),
+        "UTF-8" => String::from_utf8_lossy(bytes).to_string(),
+        encoding => {
+            log::warn!(
+                "Encoding {encoding:#?} of the dictionary is not supported, reading it as UTF-8"
+            );
+            String::from_utf8_lossy(bytes).to_string()
+        }
+    }
+}
+
+/// Split ".dic" entry into the stem and its flags, e.g. "walk/DGS po:verb"
+fn parse_entry(line: &str) -> (String, &str) {
+    let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+    let mut slash: Option<usize> = None;
+    let mut previous = ' ';
+    for (index, c) in entry.char_indices() {
+        if c == '/' && previous != '\\' {
+            slash = Some(index);
+            break;
+        }
+        previous = c;
+    }
+    match slash {
+        Some(index) => (entry[..index].replace("\\/", "/"), &entry[index + 1..]),
+        None => (entry.replace("\\/", "/"), ""),
+    }
+}
+
+/// All word forms of the dictionary, expanded with affixes
+pub fn expand(aff: &str, dic: &str) -> Vec<String> {
+    let affixes = Affixes::parse(aff);
+    let mut words: HashSet<String> = HashSet::new();
+    // First line is an approximate number of entries
+    for line in dic.lines().skip(1) {
+        // Lines starting with a tab are comments
+        if line.trim().is_empty() || line.starts_with('\t') {
+            continue;
+        }
+        let (stem, flags) = parse_entry(line);
+        words.extend(affixes.expand(&stem, &affixes.flags(flags)));
+    }
+    let mut words = words.into_iter().collect::<Vec<String>>();
+    words.sort();
+    words
+}
+
+/// Read Hunspell ".dic" file and the ".aff" file next to it
+pub fn load(dic_path: &Path) -> std::io::Result<Vec<String>> {
+    let aff = std::fs::read(dic_path.with_extension("aff"))?;
+    let dic = std::fs::read(dic_path)?;
+    let encoding = String::from_utf8_lossy(&aff)
+        .lines()
+        .find_map(|line| {
+            line.trim()
+                .strip_prefix("SET ")
+                .map(|set| set.trim().to_uppercase())
+        })
+        .unwrap_or(String::from("UTF-8"));
+    Ok(expand(&decode(&aff, &encoding), &decode(&dic, &encoding)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn affixes_expanded() {
+        let aff = "SET UTF-8\n\
+                   PFX U Y 1\n\
+                   PFX U 0 un .\n\
+                   SFX S Y 2\n\
+                   SFX S y ies [^aeiou]y\n\
+                   SFX S 0 s [aeiou]y\n\
+                   NEEDAFFIX N\n";
+        let dic = "4\n\
+                   fly/S\n\
+                   toy/SU\n\
+                   Straße/N\n\
+                   Café";
+        assert_eq!(
+            vec!["Café", "flies", "fly", "toy", "toys", "untoy", "untoys"],
+            expand(aff, dic)
+        );
+    }
+
+    #[test]
+    fn flags_of_different_types_parsed() {
+        let long = Affixes::parse("FLAG long\nSFX Aa Y 1\nSFX Aa 0 en .\n");
+        assert_eq!(vec!["Aa", "Bb"], long.flags("AaBb"));
+        assert_eq!(
+            vec!["haus", "hausen"],
+            long.expand("haus", &long.flags("Aa"))
+        );
+
+        let aliased = Affixes::parse("FLAG num\nAF 2\nAF 1,2\nAF 3\n");
+        assert_eq!(vec!["3"], aliased.flags("2"));
+        assert_eq!(vec!["1", "2"], aliased.flags("1"));
+    }
+}
@@ -0,0 +1,154 @@
+use common::{Config, MarkDownFile, SpellingConfig};
+use std::path::Path;
+use wildmatch::WildMatch;
+
+/// Language of the file: "lang" from its front matter, then language of the first
+/// dictionary whose "files" match the file, then the default one from the config
+pub fn detect_language(file: &MarkDownFile, config: &Config) -> String {
+    let spelling = &config.spelling;
+    if let Some(lang) = front_matter_lang(&file.content) {
+        return resolve(&lang, spelling);
+    }
+    let path = relative_path(&file.path, config).replace('\\', "/");
+    spelling
+        .dictionaries
+        .iter()
+        .find(|dictionary| {
+            dictionary
+                .files
+                .iter()
+                .any(|pattern| WildMatch::new(pattern).matches(&path))
+        })
+        .map_or_else(
+            || resolve(&spelling.language, spelling),
+            |dictionary| normalize(&dictionary.lang),
+        )
+}
+
+/// Path of the file relative to the config file(or to the current dir when
+/// there is no config file), since "files" of dictionaries are written relative to it
+fn relative_path(path: &str, config: &Config) -> String {
+    let base_dir = match &config.location {
+        Some(location) => Path::new(location).parent().map(Path::to_path_buf),
+        None => std::env::current_dir().ok(),
+    };
+    base_dir
+        .and_then(|dir| Path::new(path).strip_prefix(dir).ok())
+        .map_or_else(|| path.to_string(), |path| path.display().to_string())
+}
+
+/// Language that has a dictionary, e.g. "de" for "de-CH" when there is no Swiss one
+fn resolve(lang: &str, config: &SpellingConfig) -> String {
+    let lang = normalize(lang);
+    let is_known = |lang: &str| {
+        lang == "en"
+            || config
+                .dictionaries
+                .iter()
+                .any(|dictionary| matches(&dictionary.lang, lang))
+    };
+    if is_known(&lang) {
+        return lang;
+    }
+    let primary = lang.split('-').next().unwrap_or_default().to_string();
+    if is_known(&primary) {
+        return primary;
+    }
+    log::warn!("There is no dictionary for the language {lang:#?}, English is used");
+    String::from("en")
+}
+
+/// Whether the dictionary is for the normalized language. Language without
+/// a region matches dictionaries of all regions, e.g. "de" matches "de_DE"
+pub fn matches(dictionary_lang: &str, lang: &str) -> bool {
+    let dictionary_lang = normalize(dictionary_lang);
+    dictionary_lang == lang
+        || (!lang.contains('-') && dictionary_lang.split('-').next() == Some(lang))
+}
+
+/// "de_DE" and "de-de" are the same language
+pub fn normalize(lang: &str) -> String {
+    lang.trim().replace('_', "-").to_lowercase()
+}
+
+/// Value of "lang" in the YAML("---") or TOML("+++") front matter
+fn front_matter_lang(content: &str) -> Option<String> {
+    let mut lines = content.lines();
+    let fence = lines.next()?.trim_end();
+    if fence != "---" && fence != "+++" {
+        return None;
+    }
+    lines
+        .take_while(|line| line.trim_end() != fence)
+        .find_map(|line| {
+            let (key, value) = line.split_once([':', '='])?;
+            (key.trim() == "lang").then(|| value.trim().trim_matches(['"', '\'']).to_string())
+        })
+        .filter(|lang| !lang.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn file(path: &str, content: &str) -> MarkDownFile {
+        MarkDownFile {
+            path: path.to_owned(),
+            content: content.to_owned(),
+            issues: vec![],
+        }
+    }
+
+    #[test]
+    fn language_detected_by_front_matter_and_glob() {
+        let spelling = SpellingConfig {
+            dictionaries: vec![
+                common::DictionaryConfig {
+                    lang: String::from("de_DE"),
+                    path: String::from("de_DE.dic"),
+                    files: vec![String::from("docs/de/*")],
+                },
+                common::DictionaryConfig {
+                    lang: String::from("fr"),
+                    path: String::from("fr.dic"),
+                    files: vec![],
+                },
+            ],
+            ..SpellingConfig::default()
+        };
+        let config = Config {
+            spelling,
+            location: Some(String::from("/project/checkmark.toml")),
+            ..Config::default()
+        };
+        assert_eq!("en", detect_language(&file("README.md", "# Hi"), &config));
+        assert_eq!("de-de", detect_language(&file("docs/de/a.md", ""), &config));
+        assert_eq!(
+            "de-de",
+            detect_language(&file("/project/docs/de/a.md", ""), &config)
+        );
+        assert_eq!(
+            "en",
+            detect_language(&file("/other/docs/de/a.md", ""), &config)
+        );
+        assert_eq!(
+            "fr",
+            detect_language(&file("docs/de/a.md", "---\nlang: fr-CA\n---\n"), &config)
+        );
+        assert_eq!(
+            "fr",
+            detect_language(&file("a.md", "+++\nlang = \"fr\"\n+++\n"), &config)
+        );
+        assert_eq!(
+            "en",
+            detect_language(&file("a.md", "---\nlang: it\n---\n"), &config)
+        );
+        assert_eq!(
+            "de",
+            detect_language(&file("a.md", "---\nlang: de-CH\n---\n"), &config)
+        );
+        assert!(matches("de_DE", "de"));
+        assert!(!matches("de_DE", "de-ch"));
+    }
+}
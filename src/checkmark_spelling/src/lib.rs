@@ -1,14 +1,17 @@
+mod hunspell;
+mod language;
 mod spell_checker;
 mod text_to_words;
 
-use spell_checker::{check_spelling, create_spell_checker};
-use symspell::{AsciiStringStrategy, SymSpell};
+use language::detect_language;
+use spell_checker::{check_spelling, create_spell_checker, SpellChecker};
 use text_to_words::{text_to_words, Word};
 
 use colored::Colorize;
 use common::tui::CheckProgressTUI;
 use common::{CheckIssue, CheckIssueBuilder, IssueCategory, IssueSeverity, MarkDownFile};
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
 fn to_check_issue(
@@ -71,7 +74,7 @@ fn to_check_issue(
 /// https://github.com/reneklacan/symspell
 /// https://github.com/wolfgarbe/SymSpell
 pub fn spell_check(
-    spell_checker: &SymSpell<AsciiStringStrategy>,
+    spell_checker: &SpellChecker,
     file: &MarkDownFile,
     config: &common::Config,
 ) -> Vec<CheckIssue> {
@@ -86,19 +89,41 @@ pub fn spell_check(
 }
 
 /// Perform spell check of a list of files and fill them with issues found.
+/// Each file is checked with the spell checker of its language,
+/// which is created only once for all files of that language.
 pub fn spell_check_bulk(
     files: &mut Vec<MarkDownFile>,
     config: &common::Config,
     tui: &Arc<Mutex<CheckProgressTUI>>,
 ) {
     tui.lock().unwrap().start_spinner("Checking spelling...");
-    log::debug!("Initializing spell checker...");
-    let spell_checker = create_spell_checker(&config.spelling.words_whitelist);
-    files.par_iter_mut().for_each(|file| {
-        file.issues
-            .append(&mut spell_check(&spell_checker, file, config));
-        tui.lock().unwrap().print_file_check_status(file);
-    });
+    let languages = files
+        .iter()
+        .map(|file| detect_language(file, config))
+        .collect::<Vec<String>>();
+    log::debug!("Initializing spell checkers...");
+    let spell_checkers = languages
+        .iter()
+        .collect::<HashSet<&String>>()
+        .into_par_iter()
+        .map(|lang| {
+            let spell_checker = create_spell_checker(
+                lang,
+                &config.spelling.dictionaries,
+                &config.spelling.words_whitelist,
+            );
+            (lang, spell_checker)
+        })
+        .collect::<HashMap<&String, SpellChecker>>();
+    files
+        .par_iter_mut()
+        .zip(&languages)
+        .for_each(|(file, lang)| {
+            log::debug!("Spelling of {:#?} is checked in {lang:#?}", &file.path);
+            file.issues
+                .append(&mut spell_check(&spell_checkers[lang], file, config));
+            tui.lock().unwrap().print_file_check_status(file);
+        });
 }
 
 #[cfg(test)]
@@ -114,8 +139,8 @@ mod test {
     /// Share spellchecker across tests
     /// to avoid re-creating it for each test
     #[cfg(test)]
-    static SPELL_CHECKER: once_cell::sync::Lazy<SymSpell<AsciiStringStrategy>> =
-        once_cell::sync::Lazy::new(|| create_spell_checker(&[]));
+    static SPELL_CHECKER: once_cell::sync::Lazy<SpellChecker> =
+        once_cell::sync::Lazy::new(|| create_spell_checker("en", &[], &[]));
 
     #[cfg(test)]
     fn assert_has_issues(
@@ -131,6 +156,7 @@ mod test {
         let config = common::Config {
             spelling: common::SpellingConfig {
                 words_whitelist: whitelist.to_owned(),
+                ..common::SpellingConfig::default()
             },
             ..common::Config::default()
         };
@@ -1,7 +1,51 @@
-use symspell::{AsciiStringStrategy, Suggestion, SymSpell, Verbosity};
+use crate::hunspell;
+use crate::language;
+use common::DictionaryConfig;
+use std::path::Path;
+use symspell::{Suggestion, SymSpell, UnicodeStringStrategy, Verbosity};
 
-pub fn create_spell_checker(whitelisted_words: &[String]) -> SymSpell<AsciiStringStrategy> {
-    let mut symspell: SymSpell<AsciiStringStrategy> = SymSpell::default();
+pub type SpellChecker = SymSpell<UnicodeStringStrategy>;
+
+/// Frequency of words that have no frequency of their own, like whitelisted ones
+const DEFAULT_FREQUENCY: &str = "10956800";
+
+/// Spell checker of the language with the bundled English dictionary
+/// for "en" and dictionaries from the config of the same language(of any region
+/// when language has none)
+pub fn create_spell_checker(
+    lang: &str,
+    dictionaries: &[DictionaryConfig],
+    whitelisted_words: &[String],
+) -> SpellChecker {
+    let mut symspell: SpellChecker = SymSpell::default();
+    if lang == "en" {
+        load_english(&mut symspell);
+    }
+    for dictionary in dictionaries
+        .iter()
+        .filter(|dictionary| language::matches(&dictionary.lang, lang))
+    {
+        load_dictionary(&mut symspell, Path::new(&dictionary.path));
+    }
+    // User-defined white list
+    whitelisted_words.iter().for_each(|word| {
+        symspell.load_dictionary_line(
+            &format!("{} {DEFAULT_FREQUENCY}", &word.to_lowercase()),
+            0,
+            1,
+            " ",
+        );
+        symspell.load_bigram_dictionary_line(
+            &format!("{} {DEFAULT_FREQUENCY}", &word.to_lowercase()),
+            0,
+            2,
+            " ",
+        );
+    });
+    symspell
+}
+
+fn load_english(symspell: &mut SpellChecker) {
     // Initial dictionary
     include_str!("dictionaries/frequency_dictionary_en_82_765.txt")
         .lines()
@@ -17,29 +61,44 @@ pub fn create_spell_checker(whitelisted_words: &[String]) -> SymSpell<AsciiStrin
     include_str!("dictionaries/extended_frequency_dictionary.txt")
         .lines()
         .for_each(|line| {
-            let word = format!("{} 10956800", &line);
+            let word = format!("{} {DEFAULT_FREQUENCY}", &line);
             symspell.load_dictionary_line(&word, 0, 1, " ");
             symspell.load_bigram_dictionary_line(&word, 0, 2, " ");
         });
-    // User-defined white list
-    whitelisted_words.iter().for_each(|word| {
-        symspell.load_dictionary_line(&format!("{} 10956800", &word.to_lowercase()), 0, 1, " ");
-        symspell.load_bigram_dictionary_line(
-            &format!("{} 10956800", &word.to_lowercase()),
-            0,
-            2,
-            " ",
-        );
-    });
-    symspell
+}
+
+/// Load Hunspell ".dic" file or a frequency list, words without frequency get the default one
+fn load_dictionary(symspell: &mut SpellChecker, path: &Path) {
+    log::debug!("Loading dictionary {path:#?}");
+    let words = match path.extension().is_some_and(|ext| ext == "dic") {
+        true => hunspell::load(path),
+        false => std::fs::read_to_string(path).map(|content| {
+            content
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect()
+        }),
+    };
+    let words = match words {
+        Ok(words) => words,
+        Err(error) => {
+            log::warn!("Unable to load dictionary {path:#?}: {error}");
+            return;
+        }
+    };
+    for word in words {
+        let line = match word.split_once(char::is_whitespace) {
+            Some((word, frequency)) => format!("{} {}", word.to_lowercase(), frequency.trim()),
+            None => format!("{} {DEFAULT_FREQUENCY}", word.to_lowercase()),
+        };
+        symspell.load_dictionary_line(&line, 0, 1, " ");
+    }
 }
 
 // Takes a word and checks if it is miss-spelled
 // When all fine Ok is returned, otherwise Err with suggestions provided
-pub fn check_spelling(
-    spell_checker: &SymSpell<AsciiStringStrategy>,
-    word: &str,
-) -> Result<(), Vec<Suggestion>> {
+pub fn check_spelling(spell_checker: &SpellChecker, word: &str) -> Result<(), Vec<Suggestion>> {
     let suggestions = spell_checker.lookup(word, Verbosity::Top, 2);
     // SymSpell suggest same word when all fine
     // Any suggestion - word is miss-spelled and SymSpell has ideas how to fix it
@@ -90,14 +90,15 @@ impl PartialEq<&str> for Word {
 // while preserving meta information about original word and its position
 fn extract(node: &markdown::mdast::Text) -> Vec<Word> {
     node.value
-        .split_ascii_whitespace()
+        .split_whitespace()
         .filter(|word| !is_url::is_url(word))
         .flat_map(|w| w.split('-').collect::<Vec<_>>())
         .flat_map(|w| {
-            if w.to_lowercase().contains("n't") {
+            let lowercase = w.to_lowercase();
+            if lowercase.contains("n't") || lowercase.contains("n’t") {
                 vec![w]
             } else {
-                w.split('\'').collect::<Vec<_>>()
+                w.split(['\'', '’']).collect::<Vec<_>>()
             }
         })
         .flat_map(|w| w.split('/').collect::<Vec<_>>())
@@ -115,7 +116,8 @@ fn extract(node: &markdown::mdast::Text) -> Vec<Word> {
         .flat_map(|w| w.split('_').collect::<Vec<_>>())
         .flat_map(|w| w.split('"').collect::<Vec<_>>())
         .flat_map(|w| w.split('+').collect::<Vec<_>>())
-        .map(|w| (w, w.to_lowercase()))
+        // Dictionaries spell contractions with the ASCII apostrophe
+        .map(|w| (w, w.to_lowercase().replace('’', "'")))
         .filter(|(_, escaped)| !escaped.is_empty())
         .filter(|(_, escaped)| !is_ignored_word(escaped))
         .map(|(original, escaped)| {
@@ -229,5 +231,9 @@ mod tests {
             extract(&text_node("Hello(there)World fr1end 4real!?")),
             vec!["hello", "there", "world", "4real"]
         );
+        assert_eq!(
+            extract(&text_node("l’école\u{a0}? Bonjour\u{a0}: don’t")),
+            vec!["l", "école", "bonjour", "don't"]
+        );
     }
 }
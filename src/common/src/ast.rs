@@ -1,5 +1,10 @@
-use markdown::mdast::{Blockquote, Code, Heading, Html, Link, List, ListItem, Node, Strong, Text};
+use markdown::mdast::{
+    Blockquote, Code, Emphasis, Heading, Html, Link, List, ListItem, Node, Strong, Table, Text,
+};
 
+/// Iterates over all nodes of the AST in document order.
+/// Despite the name, nodes are visited depth-first(pre-order):
+/// every node is followed by its children before its next sibling
 #[derive(Debug)]
 pub struct BfsIterator<'a> {
     values: Vec<&'a Node>,
@@ -74,6 +79,42 @@ pub fn try_cast_to_strong(node: &Node) -> Option<&Strong> {
     }
 }
 
+/// Return the emphasis node if the provided generic node is an emphasis.
+/// Meant to be used in a filter_map statement to filter emphasis nodes
+/// from a generic AST.
+/// Example:
+/// ```
+/// # use markdown::mdast::{Emphasis, Node};
+/// let ast = common::ast::parse("*Emphasis*").unwrap();
+/// let emphasis_elements = common::ast::BfsIterator::from(&ast)
+///                  .filter_map(|n| common::ast::try_cast_to_emphasis(n))
+///                  .collect::<Vec<&Emphasis>>();
+/// ```
+pub fn try_cast_to_emphasis(node: &Node) -> Option<&Emphasis> {
+    match node {
+        Node::Emphasis(e) => Some(e),
+        _ => None,
+    }
+}
+
+/// Return the table node if the provided generic node is a table.
+/// Meant to be used in a filter_map statement to filter table nodes
+/// from a generic AST.
+/// Example:
+/// ```
+/// # use markdown::mdast::{Table, Node};
+/// let ast = common::ast::parse("| a |\n| - |\n| b |").unwrap();
+/// let tables = common::ast::BfsIterator::from(&ast)
+///                  .filter_map(|n| common::ast::try_cast_to_table(n))
+///                  .collect::<Vec<&Table>>();
+/// ```
+pub fn try_cast_to_table(node: &Node) -> Option<&Table> {
+    match node {
+        Node::Table(e) => Some(e),
+        _ => None,
+    }
+}
+
 /// Return the text node if the provided generic node is an text node.
 /// Meant to be used in a filter_map statement to filter text nodes
 /// from a generic AST.
@@ -205,6 +246,82 @@ pub fn try_cast_to_block_quote(node: &Node) -> Option<&Blockquote> {
     }
 }
 
+/// Return plain text of the heading, e.g. "Heading" for "# Heading"
+pub fn heading_to_text(heading: &Heading) -> String {
+    inline_to_text(&heading.children)
+}
+
+/// Return plain text of inline nodes, including text of
+/// inline code, emphasis, links, etc., e.g. "Using fmt" for "Using `fmt`"
+pub fn inline_to_text(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(t) => t.value.clone(),
+            Node::InlineCode(c) => c.value.clone(),
+            Node::Image(i) => i.alt.clone(),
+            Node::Break(_) => String::from(" "),
+            node => inline_to_text(node.children().map_or(&[], |c| c.as_slice())),
+        })
+        .collect()
+}
+
+/// Takes heading and returns fragment link of it.
+/// Link element that want to jump to this header
+/// should use this fragment. Same as GitHub does: text is lowercased,
+/// punctuation is removed and spaces are replaced with dashes.
+/// Use `Slugger` when document may have headings with the same text.
+/// Example:
+/// ```
+/// let ast = common::ast::parse("# Seek & Destroy: the `fmt` command").unwrap();
+/// let heading = common::ast::BfsIterator::from(&ast)
+///                  .find_map(|n| common::ast::try_cast_to_heading(n))
+///                  .unwrap();
+/// assert_eq!("#seek--destroy-the-fmt-command", common::ast::heading_to_fragment(heading));
+/// ```
+pub fn heading_to_fragment(heading: &Heading) -> String {
+    format!("#{}", slugify(&heading_to_text(heading)))
+}
+
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Generates unique fragments of headings in the order of their appearance,
+/// headings with the same text get "-1", "-2", etc. appended like GitHub does.
+/// Example:
+/// ```
+/// let ast = common::ast::parse("# Usage\n\n## What's new\n\n## Usage").unwrap();
+/// let mut slugger = common::ast::Slugger::default();
+/// let fragments = common::ast::BfsIterator::from(&ast)
+///                  .filter_map(|n| common::ast::try_cast_to_heading(n))
+///                  .map(|h| slugger.slug(h))
+///                  .collect::<Vec<String>>();
+/// assert_eq!(vec!["#usage", "#whats-new", "#usage-1"], fragments);
+/// ```
+#[derive(Debug, Default)]
+pub struct Slugger {
+    occurrences: std::collections::HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn slug(&mut self, heading: &Heading) -> String {
+        let slug = slugify(&heading_to_text(heading));
+        let mut unique = slug.clone();
+        while self.occurrences.contains_key(&unique) {
+            let count = self.occurrences.entry(slug.clone()).or_default();
+            *count += 1;
+            unique = format!("{slug}-{count}");
+        }
+        self.occurrences.insert(unique.clone(), 0);
+        format!("#{unique}")
+    }
+}
+
 /// Parse Markdown file into an AST
 pub fn parse(source: &str) -> Result<Node, markdown::message::Message> {
     let options = markdown::ParseOptions {
@@ -0,0 +1,215 @@
+//! Hierarchical section numbers in headings, e.g. "2.3.1 Error handling".
+//! Shared between formatter, which inserts, updates or strips them, and linter, which reports incorrect ones.
+
+use crate::ast::BfsIterator;
+use crate::{HeadingNumberingConfig, HeadingNumberingStyle};
+use markdown::mdast::Node;
+use markdown::unist::Position;
+
+/// Heading which number differs from the expected one
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncorrectNumber {
+    /// Position of the whole heading
+    pub position: Position,
+    /// Offsets of the current number, including whitespace that follows it.
+    /// Empty range at the beginning of the heading's content when there's no number
+    pub prefix: std::ops::Range<usize>,
+    /// Number as it is written now
+    pub actual: Option<String>,
+    /// Number that heading shall have, None when it shall not be numbered
+    pub expected: Option<String>,
+}
+
+/// Split text into a leading section number and the rest of it,
+/// e.g. "2.3.1 Error handling" -> ("2.3.1", "Error handling").
+/// Only numbers in the form this module generates are recognized: one to six
+/// dot-separated groups of up to three digits without leading zeros, followed by whitespace.
+/// The number of groups is not tied to the level of the heading, so the number of
+/// a section moved to another level is recognized and replaced. "2024 Roadmap" is not numbered
+pub fn split_number(text: &str) -> Option<(&str, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let number = &text[..end];
+    let is_number = (1..=6).contains(&number.split('.').count())
+        && number
+            .split('.')
+            .all(|group| (1..=3).contains(&group.len()) && !group.starts_with('0'));
+    let rest = &text[end..];
+    match is_number && rest.starts_with(char::is_whitespace) {
+        true => Some((number, rest.trim_start())),
+        false => None,
+    }
+}
+
+/// Walk through headings in the order of appearance and return those
+/// which numbers do not match the configured style.
+/// Heading that skips levels is numbered as a direct child of the previous one,
+/// e.g. "####" right after "##" numbered "1" gets "1.1", not "1.0.1"
+pub fn check(ast: &Node, config: &HeadingNumberingConfig) -> Vec<IncorrectNumber> {
+    if config.style == HeadingNumberingStyle::Preserve {
+        return vec![];
+    }
+    let mut counters: Vec<usize> = vec![];
+    let mut incorrect: Vec<IncorrectNumber> = vec![];
+    for heading in BfsIterator::from(ast).filter_map(|n| crate::ast::try_cast_to_heading(n)) {
+        if heading.depth < config.start_depth {
+            // Numbering starts over in every section above the start depth
+            counters.clear();
+            continue;
+        }
+        let level = usize::from(heading.depth - config.start_depth).min(counters.len());
+        counters.truncate(level + 1);
+        counters.resize(level + 1, 0);
+        counters[level] += 1;
+        let (position, first_child) = match (&heading.position, heading.children.first()) {
+            (Some(position), Some(first_child)) => (position, first_child),
+            _ => continue,
+        };
+        let start = match first_child.position() {
+            Some(p) => p.start.offset,
+            None => continue,
+        };
+        let (actual, prefix) = match first_child {
+            Node::Text(t) => match split_number(&t.value) {
+                Some((number, rest)) => (
+                    Some(number.to_string()),
+                    start..start + t.value.len() - rest.len(),
+                ),
+                None => (None, start..start),
+            },
+            _ => (None, start..start),
+        };
+        let expected = match config.style {
+            HeadingNumberingStyle::Numbered => Some(
+                counters
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join("."),
+            ),
+            _ => None,
+        };
+        if actual != expected {
+            incorrect.push(IncorrectNumber {
+                position: position.clone(),
+                prefix,
+                actual,
+                expected,
+            });
+        }
+    }
+    incorrect
+}
+
+/// Insert, update or strip numbers of headings according to the config.
+/// Returns source as it is when numbering is preserved
+pub fn update(source: &str, config: &HeadingNumberingConfig) -> String {
+    let ast = match crate::ast::parse(source) {
+        Ok(ast) => ast,
+        Err(_) => return source.to_string(),
+    };
+    let mut result = source.to_string();
+    // Go from the end, so offsets of headings that are not yet updated remain valid
+    for heading in check(&ast, config).iter().rev() {
+        let number = match &heading.expected {
+            Some(number) => format!("{number} "),
+            None => String::new(),
+        };
+        result.replace_range(heading.prefix.clone(), &number);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_split() {
+        assert_eq!(
+            Some(("2.3.1", "Error handling")),
+            split_number("2.3.1 Error handling")
+        );
+        assert_eq!(Some(("12", "Intro")), split_number("12 Intro"));
+        assert_eq!(
+            Some(("1.2.3.4.5.6", "Deep")),
+            split_number("1.2.3.4.5.6 Deep")
+        );
+        assert_eq!(None, split_number("1.2.3.4.5.6.7 Too deep"));
+        assert_eq!(None, split_number("1. Intro"));
+        assert_eq!(None, split_number("1.2.3"));
+        assert_eq!(None, split_number("1..2 Intro"));
+        assert_eq!(None, split_number("v1.2 Intro"));
+        assert_eq!(None, split_number("2024 Roadmap"));
+        assert_eq!(None, split_number("1.02 Intro"));
+    }
+
+    #[test]
+    fn numbers_updated() {
+        let source = "# Spec\n\n## Intro\n\n## 3 Errors\n\n### 1.1 Handling\n\n### *Codes*\n\n# Appendix\n\n## 7 Links\n";
+        assert_eq!(
+            "# Spec\n\n## 1 Intro\n\n## 2 Errors\n\n### 2.1 Handling\n\n### 2.2 *Codes*\n\n# Appendix\n\n## 1 Links\n",
+            update(
+                source,
+                &HeadingNumberingConfig {
+                    style: HeadingNumberingStyle::Numbered,
+                    start_depth: 2,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn numbers_stripped() {
+        let source = "# 1 Spec\n\n## 1.1 Intro\n\n## Errors\n\n## 2024 Roadmap\n";
+        assert_eq!(
+            "# Spec\n\n## Intro\n\n## Errors\n\n## 2024 Roadmap\n",
+            update(
+                source,
+                &HeadingNumberingConfig {
+                    style: HeadingNumberingStyle::Unnumbered,
+                    start_depth: 1,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn numbers_follow_document_order_without_gaps() {
+        let source = "## Intro\n\n#### Skipped\n\n### Nested\n\n> ## Quoted\n\n## 2024 Roadmap\n";
+        assert_eq!(
+            "## 1 Intro\n\n#### 1.1 Skipped\n\n### 1.2 Nested\n\n> ## 2 Quoted\n\n## 3 2024 Roadmap\n",
+            update(
+                source,
+                &HeadingNumberingConfig {
+                    style: HeadingNumberingStyle::Numbered,
+                    start_depth: 2,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn numbers_of_moved_sections_replaced() {
+        let source = "## 1 Intro\n\n## 2.1 Moved up\n\n### 3 Moved down\n\n#### 2.1.1.1 Deep\n";
+        let numbered = |style| HeadingNumberingConfig {
+            style,
+            start_depth: 2,
+        };
+        assert_eq!(
+            "## 1 Intro\n\n## 2 Moved up\n\n### 2.1 Moved down\n\n#### 2.1.1 Deep\n",
+            update(source, &numbered(HeadingNumberingStyle::Numbered))
+        );
+        assert_eq!(
+            "## Intro\n\n## Moved up\n\n### Moved down\n\n#### Deep\n",
+            update(source, &numbered(HeadingNumberingStyle::Unnumbered))
+        );
+    }
+
+    #[test]
+    fn numbers_preserved() {
+        let source = "# 3 Spec\n\n## Intro\n";
+        assert_eq!(source, update(source, &HeadingNumberingConfig::default()));
+    }
+}
@@ -29,7 +29,11 @@
 //! CheckIssue::to_sarif_result() panics if any of the required fields has not been set.
 
 pub mod ast;
+pub mod heading_numbering;
+pub mod network;
+pub mod toc;
 pub mod tui;
+pub mod typography;
 
 /// Represents single markdown file under check
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -331,25 +335,29 @@ pub fn find_index(source: &str, sub_str: &str) -> core::ops::Range<usize> {
 /// line_number - line number to find offset for. Starts at 1
 /// returns offset of the line relative to the beginning of the text
 pub fn find_offset_by_line_number(text: &str, line_number: usize) -> usize {
-    // Detect whether text uses CRLF or LF.
-    // This affects our calculation because
-    // in CRLF each newline is incremented
-    // by 2 characters
-    let num_chars_newline = match text.contains("\r\n") {
-        true => 2,  // CRLF
-        false => 1, // LF
-    };
-    let mut pos: usize = 0;
-    for (i, line) in text.lines().enumerate() {
-        if i < line_number {
-            pos += line.len();
-            pos += num_chars_newline;
-            continue;
-        } else {
-            break;
-        }
+    // Sum up the actual length of each line including its terminator.
+    // This way both CRLF("\r\n" is 2 characters) and LF as well
+    // as files with mixed line endings are handled correctly.
+    // Last line without a terminator is counted as if it had one
+    text.split_inclusive('\n')
+        .take(line_number)
+        .map(|line| match line.ends_with('\n') {
+            true => line.len(),
+            false => line.len() + detect_line_ending(text).len(),
+        })
+        .sum()
+}
+
+/// Detect dominant line ending in the text.
+/// Returns "\r\n" when most of the lines are CRLF-terminated, "\n" otherwise
+pub fn detect_line_ending(text: &str) -> &'static str {
+    let num_crlf = text.matches("\r\n").count();
+    let num_lf = text.matches('\n').count() - num_crlf;
+    if num_crlf > num_lf {
+        "\r\n"
+    } else {
+        "\n"
     }
-    pos
 }
 
 #[cfg(test)]
@@ -373,6 +381,24 @@ mod tests {
         let actual_offset = find_offset_by_line_number(text, 1);
         assert_eq!(expected_offset, actual_offset);
     }
+
+    // Check that index consider mixed CRLF and LF
+    #[test]
+    fn offset_by_line_number_mixed() {
+        let text = "Line 1\r\nLine 2\nLine 3\r\nLine 4\n";
+        let expected_offset = 23;
+        let actual_offset = find_offset_by_line_number(text, 3);
+        assert_eq!(expected_offset, actual_offset);
+    }
+
+    // Check that dominant line ending detected
+    #[test]
+    fn dominant_line_ending() {
+        assert_eq!("\n", detect_line_ending("Line 1\nLine 2\n"));
+        assert_eq!("\r\n", detect_line_ending("Line 1\r\nLine 2\r\n"));
+        assert_eq!("\r\n", detect_line_ending("Line 1\r\nLine 2\r\nLine 3\n"));
+        assert_eq!("\n", detect_line_ending("Line 1"));
+    }
 }
 
 /// Force activate debug logging
@@ -450,6 +476,15 @@ pub struct GlobalConfig {
     pub exclude_license: bool,
 }
 
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    #[default]
+    Auto,
+    Lf,
+    Crlf,
+}
+
 #[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct FmtConfig {
     #[serde(default)]
@@ -457,6 +492,106 @@ pub struct FmtConfig {
 
     #[serde(default)]
     pub show_diff: bool,
+
+    #[serde(default)]
+    pub line_endings: LineEndings,
+
+    /// Normalize fenced JSON and TOML code blocks
+    #[serde(default)]
+    pub format_code_blocks: bool,
+
+    /// External formatters of fenced code blocks per language,
+    /// e.g. rust = "rustfmt --emit stdout"
+    #[serde(default)]
+    pub code_blocks: std::collections::BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub references: ReferencesConfig,
+
+    #[serde(default)]
+    pub typography: TypographyConfig,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotesStyle {
+    /// Keep quotes as they are
+    #[default]
+    Preserve,
+    /// Use “curly” quotes
+    Curly,
+    /// Use "straight" quotes
+    Straight,
+}
+
+/// Typographic normalization of prose. Applied to text only,
+/// code, HTML and URLs are never changed
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct TypographyConfig {
+    #[serde(default)]
+    pub quotes: QuotesStyle,
+
+    /// Replace "--" with en dash and "---" with em dash
+    #[serde(default)]
+    pub dashes: bool,
+
+    /// Replace "..." with ellipsis
+    #[serde(default)]
+    pub ellipsis: bool,
+
+    /// Put non-breaking space between a number and a unit, e.g. "10 kg"
+    #[serde(default)]
+    pub nbsp_before_units: bool,
+
+    /// Collapse multiple spaces after the end of a sentence
+    #[serde(default)]
+    pub single_space_after_period: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkReferenceStyle {
+    /// Keep links as they are
+    #[default]
+    Preserve,
+    /// Convert inline links to reference links
+    Reference,
+    /// Convert reference links to inline links
+    Inline,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefinitionsPlacement {
+    /// Keep definitions where they are
+    #[default]
+    Preserve,
+    /// Collect all definitions at the end of the document
+    Document,
+    /// Collect definitions at the end of the section where they are used first
+    Section,
+}
+
+/// How fmt organizes links and their definitions
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct ReferencesConfig {
+    #[serde(default)]
+    pub links: LinkReferenceStyle,
+
+    #[serde(default)]
+    pub definitions: DefinitionsPlacement,
+
+    /// Sort definitions by label
+    #[serde(default)]
+    pub sort: bool,
+
+    /// Lowercase labels and collapse whitespace in them
+    #[serde(default)]
+    pub normalize_labels: bool,
+
+    /// Drop definitions that are not referenced
+    #[serde(default)]
+    pub remove_unused: bool,
 }
 
 #[derive(Debug, Default, Clone, serde::Deserialize)]
@@ -487,6 +622,80 @@ pub enum BoldStyle {
     Underscore,
 }
 
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmphasisStyle {
+    #[default]
+    Consistent,
+    Asterisk,
+    Underscore,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThematicBreakStyle {
+    #[default]
+    Consistent,
+    Dash,
+    Asterisk,
+    Underscore,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeFenceStyle {
+    #[default]
+    Consistent,
+    Backtick,
+    Tilde,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderedListStyle {
+    #[default]
+    Consistent,
+    One,
+    Ordered,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderedListDelimiterStyle {
+    #[default]
+    Consistent,
+    Period,
+    Parenthesis,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    Consistent,
+    #[default]
+    Aligned,
+    Compact,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutolinkStyle {
+    Consistent,
+    #[default]
+    Angle,
+    Bare,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListItemSpacingStyle {
+    #[default]
+    Preserve,
+    Consistent,
+    Tight,
+    Loose,
+}
+
 #[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct StyleConfig {
     #[serde(default)]
@@ -503,6 +712,116 @@ pub struct StyleConfig {
 
     #[serde(default)]
     pub default_code_block_language: Option<String>,
+
+    #[serde(default)]
+    pub emphasis: EmphasisStyle,
+
+    #[serde(default)]
+    pub thematic_break: ThematicBreakStyle,
+
+    #[serde(default)]
+    pub code_fence: CodeFenceStyle,
+
+    #[serde(default)]
+    pub code_fence_length: Option<u8>,
+
+    #[serde(default)]
+    pub ordered_lists: OrderedListStyle,
+
+    #[serde(default)]
+    pub ordered_list_delimiter: OrderedListDelimiterStyle,
+
+    #[serde(default)]
+    pub tables: TableStyle,
+
+    #[serde(default)]
+    pub max_table_column_width: Option<u16>,
+
+    #[serde(default)]
+    pub autolinks: AutolinkStyle,
+
+    #[serde(default)]
+    pub list_item_spacing: ListItemSpacingStyle,
+
+    #[serde(default)]
+    pub toc: TocConfig,
+
+    #[serde(default)]
+    pub heading_numbering: HeadingNumberingConfig,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TocStyle {
+    #[default]
+    Unordered,
+    Ordered,
+}
+
+/// Table of contents placed between "<!-- toc -->" and "<!-- tocstop -->"
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TocConfig {
+    /// Headings with lower depth are not included
+    #[serde(default = "get_default_toc_min_depth")]
+    pub min_depth: u8,
+
+    /// Headings with higher depth are not included
+    #[serde(default = "get_default_toc_max_depth")]
+    pub max_depth: u8,
+
+    #[serde(default)]
+    pub style: TocStyle,
+}
+
+impl Default for TocConfig {
+    fn default() -> Self {
+        Self {
+            min_depth: get_default_toc_min_depth(),
+            max_depth: get_default_toc_max_depth(),
+            style: TocStyle::default(),
+        }
+    }
+}
+
+fn get_default_toc_min_depth() -> u8 {
+    1
+}
+
+fn get_default_toc_max_depth() -> u8 {
+    6
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingNumberingStyle {
+    #[default]
+    Preserve,
+    Numbered,
+    Unnumbered,
+}
+
+/// Hierarchical section numbers in headings, e.g. "2.3.1 Error handling"
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct HeadingNumberingConfig {
+    #[serde(default)]
+    pub style: HeadingNumberingStyle,
+
+    /// Headings with lower depth are not numbered and restart numbering
+    #[serde(default = "get_default_heading_numbering_start_depth")]
+    pub start_depth: u8,
+}
+
+impl Default for HeadingNumberingConfig {
+    fn default() -> Self {
+        Self {
+            style: HeadingNumberingStyle::default(),
+            start_depth: get_default_heading_numbering_start_depth(),
+        }
+    }
+}
+
+fn get_default_heading_numbering_start_depth() -> u8 {
+    1
 }
 
 #[derive(Debug, Default, Clone, serde::Deserialize)]
@@ -568,6 +887,323 @@ pub struct LinkCheckerConfig {
 
     #[serde(default)]
     pub allow_insecure: bool,
+
+    /// Check only local files, directories and fragments, skip external URLs
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Report external URLs skipped in offline mode
+    #[serde(default)]
+    pub list_skipped: bool,
+
+    /// Fetch HTML pages of external URLs with fragments and check that fragments exist on them
+    #[serde(default)]
+    pub remote_fragments: bool,
+
+    /// Directory against which root-relative links(e.g. "/docs/api.md") are resolved,
+    /// relative to the config file. Project root or the root of the Git repository when not set
+    #[serde(default)]
+    pub root_dir: Option<String>,
+
+    /// URL against which root-relative links are resolved instead of the root dir,
+    /// e.g. the address where the documentation is published
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Maximum number of concurrent requests
+    #[serde(default)]
+    pub max_concurrency: Option<u16>,
+
+    /// Maximum number of concurrent requests to the same host
+    #[serde(default)]
+    pub max_concurrency_per_host: Option<u16>,
+
+    #[serde(default)]
+    pub cache: LinkCacheConfig,
+
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    #[serde(default)]
+    pub redirects: RedirectsConfig,
+
+    #[serde(default)]
+    pub assets: AssetsConfig,
+
+    #[serde(default)]
+    pub emails: EmailsConfig,
+
+    /// Headers and authentication for requests to specific hosts
+    #[serde(default)]
+    pub hosts: Vec<HostConfig>,
+
+    /// Path to the cookie jar file in Netscape format(e.g. exported by a browser or curl)
+    #[serde(default)]
+    pub cookie_jar: Option<String>,
+
+    /// Proxy and TLS settings, also used to download remote files
+    #[serde(flatten)]
+    pub network: NetworkConfig,
+
+    /// Rules that replace URLs before checking them
+    #[serde(default)]
+    pub remap: Vec<RemapRule>,
+
+    /// Severity of link errors by their kind, e.g. "too_many_redirects"
+    #[serde(default)]
+    pub error_severity: std::collections::HashMap<String, LinkErrorSeverity>,
+}
+
+/// Proxy and TLS settings for requests to the network
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy for HTTP and HTTPS requests, e.g. "http://proxy.example.com:8080".
+    /// HTTP_PROXY/HTTPS_PROXY environment variables are used when not set
+    #[serde(default)]
+    pub proxy: Option<String>,
+
+    /// Hosts requested without the proxy, e.g. "localhost" or ".example.com"
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+
+    /// PEM files with root certificates trusted in addition to the system ones
+    #[serde(default)]
+    pub ca_certs: Vec<String>,
+
+    /// PEM file with the client certificate for mutual TLS
+    #[serde(default)]
+    pub client_cert: Option<String>,
+
+    /// PEM file with the PKCS#8 private key of the client certificate.
+    /// Key is read from the certificate file when not set
+    #[serde(default)]
+    pub client_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkErrorSeverity {
+    Error,
+    Warning,
+    Note,
+    /// Do not report this kind of errors
+    Ignore,
+}
+
+/// Results of link checks persisted between runs
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct LinkCacheConfig {
+    #[serde(default = "get_default_link_cache_enabled")]
+    pub enabled: bool,
+
+    /// Path to the cache file
+    #[serde(default = "get_default_link_cache_path")]
+    pub path: String,
+
+    /// Seconds after which entry is removed from the cache regardless of its status
+    #[serde(default = "get_default_link_cache_max_age")]
+    pub max_age: u64,
+
+    /// Seconds for which successful result is re-used
+    #[serde(default = "get_default_link_cache_success_ttl")]
+    pub success_ttl: u64,
+
+    /// Seconds for which failed result is re-used
+    #[serde(default = "get_default_link_cache_failure_ttl")]
+    pub failure_ttl: u64,
+
+    /// Ignore cached results and check every link again, cache is updated with new results
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+impl Default for LinkCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: get_default_link_cache_enabled(),
+            path: get_default_link_cache_path(),
+            max_age: get_default_link_cache_max_age(),
+            success_ttl: get_default_link_cache_success_ttl(),
+            failure_ttl: get_default_link_cache_failure_ttl(),
+            refresh: false,
+        }
+    }
+}
+
+/// Request settings for hosts that match the wildcard
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct HostConfig {
+    /// Wildcard matched against the host, e.g. "*.example.com"
+    pub host: String,
+
+    /// Headers added to every request to the host
+    #[serde(default)]
+    pub headers: std::collections::BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub auth: Option<HostAuth>,
+}
+
+/// Authentication with secrets read from environment variables,
+/// so they never end up in the config file
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HostAuth {
+    Basic {
+        username: String,
+        /// Name of the environment variable with the password
+        password_env: String,
+    },
+    Bearer {
+        /// Name of the environment variable with the token
+        token_env: String,
+    },
+}
+
+/// Replaces URLs that match the pattern before checking them
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RemapRule {
+    /// Regular expression matched against the whole URL
+    pub pattern: String,
+    /// Replacement, may refer to capture groups, e.g. "./docs/$1.md".
+    /// Replacement that is not a URL is a path relative to the root dir of links
+    pub replacement: String,
+}
+
+/// Retries of rate-limited(HTTP 429) requests and delays between requests
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RateLimitConfig {
+    /// Milliseconds to wait before the first retry, doubled with every next one.
+    /// "Retry-After" header of the response takes precedence
+    #[serde(default = "get_default_rate_limit_base_delay")]
+    pub base_delay: u64,
+
+    /// Maximum milliseconds to wait before a retry
+    #[serde(default = "get_default_rate_limit_max_delay")]
+    pub max_delay: u64,
+
+    /// Minimum milliseconds between requests to the same host
+    #[serde(default)]
+    pub min_delay_per_host: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: get_default_rate_limit_base_delay(),
+            max_delay: get_default_rate_limit_max_delay(),
+            min_delay_per_host: 0,
+        }
+    }
+}
+
+fn get_default_rate_limit_base_delay() -> u64 {
+    1000
+}
+
+fn get_default_rate_limit_max_delay() -> u64 {
+    60 * 1000
+}
+
+/// Validation of e-mail addresses, only when `check_emails` is enabled
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct EmailsConfig {
+    #[serde(default)]
+    pub mode: EmailCheckMode,
+
+    /// DNS server for MX lookups, e.g. "1.1.1.1:53". System resolver is used when not set
+    #[serde(default)]
+    pub resolver: Option<String>,
+
+    /// Wildcards of domains whose addresses are always valid, e.g. internal domains without MX records
+    #[serde(default)]
+    pub allow_domains: Vec<String>,
+
+    /// Wildcards of domains whose addresses are always reported, e.g. disposable mail services
+    #[serde(default)]
+    pub deny_domains: Vec<String>,
+}
+
+/// How e-mail addresses are validated. Invalid addresses and placeholders
+/// like "user@example.com" are reported in every mode
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailCheckMode {
+    /// Check that mail server accepts the address, needs outbound SMTP
+    #[default]
+    Smtp,
+    /// Check that domain has a mail server(MX record)
+    Mx,
+    /// Check only syntax of the address, without network access
+    Syntax,
+}
+
+/// Images and attachments stored next to the documents
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct AssetsConfig {
+    /// Directories with assets, relative to the root dir.
+    /// Files in them that no document links to are reported as unused
+    #[serde(default)]
+    pub dirs: Vec<String>,
+
+    /// Wildcards of asset files that are never reported as unused, e.g. "*.ico"
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+/// Reporting of redirected links
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RedirectsConfig {
+    /// Report links that are permanently redirected(301, 308)
+    #[serde(default = "get_default_report_redirects")]
+    pub report: bool,
+
+    /// Do not report redirects that only add a trailing slash
+    #[serde(default)]
+    pub ignore_trailing_slash: bool,
+
+    /// Do not report redirects that only switch from http:// to https://
+    #[serde(default)]
+    pub ignore_https_upgrade: bool,
+
+    /// Replace redirected links with their final URL in the source
+    #[serde(default)]
+    pub fix: bool,
+}
+
+impl Default for RedirectsConfig {
+    fn default() -> Self {
+        Self {
+            report: get_default_report_redirects(),
+            ignore_trailing_slash: false,
+            ignore_https_upgrade: false,
+            fix: false,
+        }
+    }
+}
+
+fn get_default_report_redirects() -> bool {
+    true
+}
+
+fn get_default_link_cache_enabled() -> bool {
+    true
+}
+
+fn get_default_link_cache_path() -> String {
+    String::from(".checkmark/linkcache")
+}
+
+fn get_default_link_cache_max_age() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+fn get_default_link_cache_success_ttl() -> u64 {
+    24 * 60 * 60
+}
+
+fn get_default_link_cache_failure_ttl() -> u64 {
+    60 * 60
 }
 
 #[derive(Debug, Default, Clone, serde::Deserialize)]
@@ -582,10 +1218,48 @@ pub struct LinterConfig {
     pub exclude: Vec<String>,
 }
 
-#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct SpellingConfig {
     #[serde(default)]
     pub words_whitelist: Vec<String>,
+
+    /// Language of files that neither set "lang" in the front matter
+    /// nor match "files" of any dictionary
+    #[serde(default = "get_default_spelling_language")]
+    pub language: String,
+
+    /// Additional dictionaries, English is bundled
+    #[serde(default)]
+    pub dictionaries: Vec<DictionaryConfig>,
+}
+
+impl Default for SpellingConfig {
+    fn default() -> Self {
+        Self {
+            words_whitelist: vec![],
+            language: get_default_spelling_language(),
+            dictionaries: vec![],
+        }
+    }
+}
+
+fn get_default_spelling_language() -> String {
+    String::from("en")
+}
+
+/// Dictionary of a language. Dictionaries with the same language are merged
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct DictionaryConfig {
+    /// Language code, e.g. "de" or "fr"
+    pub lang: String,
+
+    /// Hunspell ".dic" file with the ".aff" file next to it,
+    /// or a frequency list with a "word count" per line. Relative to the config file
+    pub path: String,
+
+    /// Wildcards of files written in this language relative to the config file, e.g. "docs/de/*"
+    #[serde(default)]
+    pub files: Vec<String>,
 }
 
 #[derive(Debug, Default, Clone, serde::Deserialize)]
@@ -0,0 +1,46 @@
+use crate::NetworkConfig;
+use log::warn;
+use reqwest::{Certificate, ClientBuilder, Identity, NoProxy, Proxy};
+
+/// HTTP client builder with the proxy, root certificates and client certificate
+/// from the config. Settings that can't be read are skipped with a warning
+pub fn client_builder(config: &NetworkConfig) -> ClientBuilder {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = &config.proxy {
+        match Proxy::all(proxy) {
+            Ok(proxy) => {
+                let no_proxy = NoProxy::from_string(&config.no_proxy.join(","));
+                builder = builder.proxy(proxy.no_proxy(no_proxy));
+            }
+            Err(error) => warn!("Ignoring invalid proxy {proxy:#?}: {error}"),
+        }
+    }
+    for path in &config.ca_certs {
+        match read(path)
+            .and_then(|pem| Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string()))
+        {
+            Ok(certificates) => {
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            Err(error) => warn!("Ignoring CA certificates {path:#?}: {error}"),
+        }
+    }
+    if let Some(cert_path) = &config.client_cert {
+        let key_path = config.client_key.as_ref().unwrap_or(cert_path);
+        let identity = read(cert_path).and_then(|cert| {
+            let key = read(key_path)?;
+            Identity::from_pkcs8_pem(&cert, &key).map_err(|e| e.to_string())
+        });
+        match identity {
+            Ok(identity) => builder = builder.identity(identity),
+            Err(error) => warn!("Ignoring client certificate {cert_path:#?}: {error}"),
+        }
+    }
+    builder
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,330 @@
+//! Table of contents placed between "<!-- toc -->" and "<!-- tocstop -->" markers.
+//! Shared between formatter, which regenerates it, and linter, which reports a stale one.
+
+use crate::ast::{inline_to_text, try_cast_to_heading, BfsIterator, Slugger};
+use crate::{TocConfig, TocStyle};
+use markdown::mdast::Node;
+use markdown::unist::Position;
+
+/// Single line of the table of contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    pub depth: u8,
+    pub text: String,
+    pub fragment: String,
+}
+
+/// Where table of contents is located in the document
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocLocation {
+    /// From the beginning of the start marker till the end of the end marker
+    pub position: Position,
+    /// Offsets of the content between markers
+    pub content: std::ops::Range<usize>,
+}
+
+/// Returns true when HTML is a comment with given text, e.g. "<!-- toc -->"
+fn is_marker(html: &str, marker: &str) -> bool {
+    html.trim()
+        .strip_prefix("<!--")
+        .and_then(|s| s.strip_suffix("-->"))
+        .is_some_and(|s| s.trim().eq_ignore_ascii_case(marker))
+}
+
+/// Find table of contents markers in the document.
+/// Returns None when document has no markers
+pub fn locate(ast: &Node) -> Option<TocLocation> {
+    let html_nodes = BfsIterator::from(ast).filter_map(|n| match n {
+        Node::Html(html) => Some(html),
+        _ => None,
+    });
+    let mut start: Option<&Position> = None;
+    for html in html_nodes {
+        if is_marker(&html.value, "toc") && start.is_none() {
+            start = html.position.as_ref();
+        } else if is_marker(&html.value, "tocstop") {
+            if let (Some(start), Some(end)) = (start, html.position.as_ref()) {
+                return Some(TocLocation {
+                    position: Position {
+                        start: start.start.clone(),
+                        end: end.end.clone(),
+                    },
+                    content: start.end.offset..end.start.offset,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Collect headings that shall be listed in the table of contents.
+/// Headings with the same text get "-1", "-2", etc. appended to their fragments,
+/// counted over all headings of the document like GitHub does
+pub fn collect(ast: &Node, config: &TocConfig) -> Vec<TocEntry> {
+    let mut slugger = Slugger::default();
+    BfsIterator::from(ast)
+        .filter_map(|n| try_cast_to_heading(n))
+        .map(|h| (h, slugger.slug(h)))
+        .filter(|(h, _)| h.depth >= config.min_depth && h.depth <= config.max_depth)
+        .map(|(h, fragment)| TocEntry {
+            depth: h.depth,
+            text: to_entry_text(&h.children),
+            fragment,
+        })
+        .filter(|e| !e.text.trim().is_empty())
+        .collect()
+}
+
+/// Render inline content of the heading as a text of the link.
+/// Formatting is preserved, links are replaced by their text
+/// since links can't be nested
+fn to_entry_text(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(t) => t.value.replace('[', "\\[").replace(']', "\\]"),
+            Node::InlineCode(c) => {
+                let fence = "`".repeat(longest_backtick_run(&c.value) + 1);
+                let padding = match c.value.starts_with('`') || c.value.ends_with('`') {
+                    true => " ",
+                    false => "",
+                };
+                format!("{fence}{padding}{}{padding}{fence}", c.value)
+            }
+            Node::Emphasis(e) => format!("*{}*", to_entry_text(&e.children)),
+            Node::Strong(s) => format!("**{}**", to_entry_text(&s.children)),
+            Node::Delete(d) => format!("~~{}~~", to_entry_text(&d.children)),
+            Node::Html(_) => String::new(),
+            node => inline_to_text(std::slice::from_ref(node)),
+        })
+        .collect()
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Entry of the table of contents that may have nested entries
+struct Parent {
+    depth: u8,
+    indent: usize,
+    marker_width: usize,
+    number: usize,
+}
+
+/// Render table of contents as a (nested) Markdown list of links.
+/// Entry is nested under the closest preceding entry of a smaller depth,
+/// so skipped levels(e.g. "##" followed by "####") add a single level of nesting
+pub fn render(entries: &[TocEntry], config: &TocConfig) -> String {
+    let mut parents: Vec<Parent> = vec![];
+    let mut lines: Vec<String> = vec![];
+    for entry in entries {
+        // Last entry removed from the stack is the previous item of the same list
+        let mut sibling: Option<Parent> = None;
+        while parents.last().is_some_and(|p| p.depth >= entry.depth) {
+            sibling = parents.pop();
+        }
+        let number = sibling.map_or(1, |s| s.number + 1);
+        // Nested list is indented by the width of the parent item's marker, e.g. "10. "
+        let indent = parents.last().map_or(0, |p| p.indent + p.marker_width);
+        let marker = match config.style {
+            TocStyle::Unordered => String::from("- "),
+            TocStyle::Ordered => format!("{number}. "),
+        };
+        lines.push(format!(
+            "{}{marker}[{}]({})",
+            " ".repeat(indent),
+            entry.text,
+            entry.fragment
+        ));
+        parents.push(Parent {
+            depth: entry.depth,
+            indent,
+            marker_width: marker.len(),
+            number,
+        });
+    }
+    lines.join("\n")
+}
+
+/// Returns true when table of contents between markers lists the same links
+/// with the same nesting and list style as the rendered one.
+/// List markers themselves are not compared, they are up to the formatter
+pub fn is_up_to_date(source: &str, location: &TocLocation, rendered: &str) -> bool {
+    let outline = |toc: &str| {
+        let mut lines: Vec<String> = vec![];
+        if let Ok(ast) = crate::ast::parse(toc) {
+            to_outline(&ast, 0, &mut lines);
+        }
+        lines
+    };
+    match source.get(location.content.clone()) {
+        Some(actual) => outline(actual) == outline(rendered),
+        None => false,
+    }
+}
+
+/// Lists and links of the document as lines, e.g. "  1. [Title](#title)"
+fn to_outline(node: &Node, level: usize, lines: &mut Vec<String>) {
+    match node {
+        Node::List(list) => {
+            let marker = if list.ordered { "1." } else { "-" };
+            for item in &list.children {
+                lines.push(format!("{}{marker}", "  ".repeat(level)));
+                for child in item.children().into_iter().flatten() {
+                    to_outline(child, level + 1, lines);
+                }
+            }
+        }
+        Node::Link(link) => {
+            let text = inline_to_text(&link.children);
+            lines.push(format!("{}[{text}]({})", "  ".repeat(level), link.url));
+        }
+        node => {
+            for child in node.children().into_iter().flatten() {
+                to_outline(child, level, lines);
+            }
+        }
+    }
+}
+
+/// Replace content between table of contents markers with the
+/// one generated from the document's headings.
+/// Returns source as it is when there are no markers
+pub fn update(source: &str, config: &TocConfig) -> String {
+    let ast = match crate::ast::parse(source) {
+        Ok(ast) => ast,
+        Err(_) => return source.to_string(),
+    };
+    match locate(&ast) {
+        Some(location) => {
+            let toc = render(&collect(&ast, config), config);
+            let mut result = source.to_string();
+            let content = match toc.is_empty() {
+                true => String::from("\n\n"),
+                false => format!("\n\n{toc}\n\n"),
+            };
+            result.replace_range(location.content, &content);
+            result
+        }
+        None => source.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toc_updated() {
+        let source = "# Title\n\n<!-- toc -->\n- [Old](#old)\n<!-- tocstop -->\n\n## One & Two\n\n### Three\n\n## Four\n";
+        assert_eq!(
+            "# Title\n\n<!-- toc -->\n\n- [Title](#title)\n  - [One & Two](#one--two)\n    - [Three](#three)\n  - [Four](#four)\n\n<!-- tocstop -->\n\n## One & Two\n\n### Three\n\n## Four\n",
+            update(source, &TocConfig::default())
+        );
+    }
+
+    #[test]
+    fn toc_ordered_with_depth_range() {
+        let source = "# Title\n\n<!-- toc -->\n<!-- tocstop -->\n\n## One\n\n### Three\n\n#### Skipped\n\n## Four\n\n### Five\n";
+        let config = TocConfig {
+            min_depth: 2,
+            max_depth: 3,
+            style: TocStyle::Ordered,
+        };
+        assert_eq!(
+            "1. [One](#one)\n   1. [Three](#three)\n2. [Four](#four)\n   1. [Five](#five)",
+            render(
+                &collect(&crate::ast::parse(source).unwrap(), &config),
+                &config
+            )
+        );
+    }
+
+    #[test]
+    fn toc_in_document_order_with_unique_fragments() {
+        let source = "# Title\n\n> ## Quoted\n\n## Usage\n\n### Usage\n\n## Usage\n";
+        assert_eq!(
+            "- [Title](#title)\n  - [Quoted](#quoted)\n  - [Usage](#usage)\n    - [Usage](#usage-1)\n  - [Usage](#usage-2)",
+            render(
+                &collect(&crate::ast::parse(source).unwrap(), &TocConfig::default()),
+                &TocConfig::default()
+            )
+        );
+    }
+
+    #[test]
+    fn toc_ordered_nested_under_two_digit_number() {
+        let entries = (1..=10)
+            .map(|n| TocEntry {
+                depth: 1,
+                text: format!("{n}"),
+                fragment: format!("#{n}"),
+            })
+            .chain(std::iter::once(TocEntry {
+                depth: 2,
+                text: String::from("Nested"),
+                fragment: String::from("#nested"),
+            }))
+            .collect::<Vec<TocEntry>>();
+        let config = TocConfig {
+            style: TocStyle::Ordered,
+            ..TocConfig::default()
+        };
+        assert!(render(&entries, &config).ends_with("\n10. [10](#10)\n    1. [Nested](#nested)"));
+    }
+
+    #[test]
+    fn toc_outdated_when_nesting_or_style_differs() {
+        let rendered = "- [Title](#title)\n  - [One](#one)";
+        let up_to_date = |source: &str| {
+            let ast = crate::ast::parse(source).unwrap();
+            is_up_to_date(source, &locate(&ast).unwrap(), rendered)
+        };
+        assert!(up_to_date(
+            "<!-- toc -->\n\n* [Title](#title)\n  * [One](#one)\n\n<!-- tocstop -->\n"
+        ));
+        assert!(!up_to_date(
+            "<!-- toc -->\n\n- [Title](#title)\n- [One](#one)\n\n<!-- tocstop -->\n"
+        ));
+        assert!(!up_to_date(
+            "<!-- toc -->\n\n1. [Title](#title)\n   1. [One](#one)\n\n<!-- tocstop -->\n"
+        ));
+    }
+
+    #[test]
+    fn toc_entry_text_keeps_inline_content() {
+        let source = "# Using `fmt` *safely*\n\n## See [docs](https://a.io) and **more**\n";
+        assert_eq!(
+            "- [Using `fmt` *safely*](#using-fmt-safely)\n  - [See docs and **more**](#see-docs-and-more)",
+            render(
+                &collect(&crate::ast::parse(source).unwrap(), &TocConfig::default()),
+                &TocConfig::default()
+            )
+        );
+    }
+
+    #[test]
+    fn toc_nested_under_closest_entry_when_levels_skipped() {
+        let source = "## One\n\n#### Deep\n\n### Three\n\n## Two\n\n#### Deep\n";
+        let ordered = TocConfig {
+            style: TocStyle::Ordered,
+            ..TocConfig::default()
+        };
+        let ast = crate::ast::parse(source).unwrap();
+        assert_eq!(
+            "- [One](#one)\n  - [Deep](#deep)\n  - [Three](#three)\n- [Two](#two)\n  - [Deep](#deep-1)",
+            render(&collect(&ast, &TocConfig::default()), &TocConfig::default())
+        );
+        assert_eq!(
+            "1. [One](#one)\n   1. [Deep](#deep)\n   2. [Three](#three)\n2. [Two](#two)\n   1. [Deep](#deep-1)",
+            render(&collect(&ast, &ordered), &ordered)
+        );
+    }
+
+    #[test]
+    fn toc_without_markers_untouched() {
+        let source = "# Title\n\n## One\n";
+        assert_eq!(source, update(source, &TocConfig::default()));
+    }
+}
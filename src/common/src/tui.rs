@@ -83,34 +83,15 @@ impl CheckProgressTUI {
     }
 
     pub fn print_report(&mut self, files: &Vec<MarkDownFile>) {
-        use codespan_reporting::diagnostic::{Diagnostic, Label, Severity};
+        use codespan_reporting::diagnostic::{Label, Severity};
         use codespan_reporting::files::SimpleFiles;
-        use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 
         let mut codespan_files = SimpleFiles::new();
         for analyzed_file in files {
             let codespan_file_id = codespan_files.add(&analyzed_file.path, &analyzed_file.content);
             for issue in &analyzed_file.issues {
-                let issue_code = match &issue.category {
-                    IssueCategory::Formatting => "Formatting",
-                    IssueCategory::Linting => "Linting",
-                    IssueCategory::LinkChecking => "LinkCheck",
-                    IssueCategory::Spelling => "Spelling",
-                    IssueCategory::Grammar => "Grammar",
-                    IssueCategory::Review => "Review",
-                };
-                let severity = match &issue.severity {
-                    IssueSeverity::Bug => Severity::Bug,
-                    IssueSeverity::Error => Severity::Error,
-                    IssueSeverity::Warning => Severity::Warning,
-                    IssueSeverity::Note => Severity::Note,
-                    IssueSeverity::Help => Severity::Help,
-                };
-                let mut codespan_diagnostic = Diagnostic::new(severity)
-                    .with_message(&issue.message)
-                    .with_code(issue_code)
-                    .with_notes(issue.fixes.clone());
-                if severity == Severity::Help {
+                let mut codespan_diagnostic = to_diagnostic(issue);
+                if codespan_diagnostic.severity == Severity::Help {
                     codespan_diagnostic = codespan_diagnostic.with_labels(vec![Label::primary(
                         codespan_file_id,
                         0..analyzed_file.content.len(),
@@ -121,20 +102,69 @@ impl CheckProgressTUI {
                         issue.offset_start..issue.offset_end,
                     )]);
                 }
-                let config = codespan_reporting::term::Config::default();
-                let writer = if self.ci_mode {
-                    StandardStream::stderr(ColorChoice::Never)
-                } else {
-                    StandardStream::stderr(ColorChoice::Auto)
-                };
-                codespan_reporting::term::emit(
-                    &mut writer.lock(),
-                    &config,
-                    &codespan_files,
-                    &codespan_diagnostic,
-                )
-                .unwrap();
+                self.emit(&codespan_files, &codespan_diagnostic);
             }
         }
     }
+
+    /// Print issues that do not point into any document, e.g. about unused assets,
+    /// so there is no source to show
+    pub fn print_issues(&mut self, issues: &[CheckIssue]) {
+        use codespan_reporting::files::SimpleFiles;
+
+        let codespan_files: SimpleFiles<&str, &str> = SimpleFiles::new();
+        for issue in issues {
+            self.had_any_issue |= issue.severity != IssueSeverity::Help;
+            let mut codespan_diagnostic = to_diagnostic(issue);
+            codespan_diagnostic.message = format!("{}: {}", issue.file_path, issue.message);
+            self.emit(&codespan_files, &codespan_diagnostic);
+        }
+    }
+
+    fn emit<'a, F: codespan_reporting::files::Files<'a, FileId = usize>>(
+        &self,
+        codespan_files: &'a F,
+        codespan_diagnostic: &codespan_reporting::diagnostic::Diagnostic<usize>,
+    ) {
+        use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+
+        let config = codespan_reporting::term::Config::default();
+        let writer = if self.ci_mode {
+            StandardStream::stderr(ColorChoice::Never)
+        } else {
+            StandardStream::stderr(ColorChoice::Auto)
+        };
+        codespan_reporting::term::emit(
+            &mut writer.lock(),
+            &config,
+            codespan_files,
+            codespan_diagnostic,
+        )
+        .unwrap();
+    }
+}
+
+/// Diagnostic with severity, message, code and notes of the issue, without labels
+fn to_diagnostic(issue: &CheckIssue) -> codespan_reporting::diagnostic::Diagnostic<usize> {
+    use codespan_reporting::diagnostic::{Diagnostic, Severity};
+
+    let issue_code = match &issue.category {
+        IssueCategory::Formatting => "Formatting",
+        IssueCategory::Linting => "Linting",
+        IssueCategory::LinkChecking => "LinkCheck",
+        IssueCategory::Spelling => "Spelling",
+        IssueCategory::Grammar => "Grammar",
+        IssueCategory::Review => "Review",
+    };
+    let severity = match &issue.severity {
+        IssueSeverity::Bug => Severity::Bug,
+        IssueSeverity::Error => Severity::Error,
+        IssueSeverity::Warning => Severity::Warning,
+        IssueSeverity::Note => Severity::Note,
+        IssueSeverity::Help => Severity::Help,
+    };
+    Diagnostic::new(severity)
+        .with_message(&issue.message)
+        .with_code(issue_code)
+        .with_notes(issue.fixes.clone())
 }
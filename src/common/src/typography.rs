@@ -0,0 +1,627 @@
+//! Typographic normalization of prose: quotes, dashes, ellipsis and spacing.
+//! Only Text nodes are touched, code, HTML and URLs are never changed.
+//! Shared between formatter, which applies it, and linter, which reports text that would change.
+
+use crate::{QuotesStyle, TypographyConfig};
+use markdown::mdast::{Node, Text};
+use markdown::unist::Position;
+
+/// Units that get a non-breaking space between them and a preceding number, e.g. "10 kg".
+/// Single-letter units, like "m" or "s", are left out: "5 s" or "3 t" is too often ordinary prose
+const UNITS: &[&str] = &[
+    "%", "‰", "°C", "°F", "nm", "µm", "mm", "cm", "km", "mg", "kg", "ml", "ns", "µs", "ms", "min",
+    "Hz", "kHz", "MHz", "GHz", "mV", "kV", "mA", "mW", "kW", "MW", "Wh", "kWh", "KB", "kB", "MB",
+    "GB", "TB", "PB", "KiB", "MiB", "GiB", "TiB", "px", "pt", "dpi", "fps", "€", "$",
+];
+
+/// Characters after which a quote is an opening one
+const OPENING_CONTEXT: &[char] = &['(', '[', '{', '“', '‘', '—', '–', '/'];
+
+/// Single kind of normalization, each is toggled separately in the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+    /// Straight vs curly quotes
+    Quotes,
+    /// "--" and "---" to en and em dashes
+    Dashes,
+    /// "..." to ellipsis
+    Ellipsis,
+    /// Non-breaking space between a number and a unit
+    UnitSpaces,
+    /// Single space after the end of a sentence
+    SentenceSpaces,
+}
+
+/// Part of the text that shall be replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    /// Byte offsets in the text
+    pub range: std::ops::Range<usize>,
+    pub replacement: &'static str,
+}
+
+/// Part of the text that would change after normalization
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Position of the characters to replace
+    pub position: Option<Position>,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl Issue {
+    /// Suggestion that tells how to fix the issue
+    pub fn fix(&self) -> String {
+        format!("Replace {:?} with {:?}", self.original, self.replacement)
+    }
+}
+
+/// Character of the Text node's value and where it is written in the source
+#[derive(Debug, Clone, PartialEq)]
+struct SourceChar {
+    /// Byte offset in the value
+    value: usize,
+    /// Byte offsets in the source
+    source: std::ops::Range<usize>,
+    /// Written as a backslash escape or a character reference, e.g. "\\\"" or "&quot;"
+    escaped: bool,
+}
+
+/// Character that is escaped in the source and kept as it is by normalization
+#[derive(Debug, Clone, PartialEq)]
+pub struct Escape {
+    /// Byte offset in the normalized value of the Text node
+    pub offset: usize,
+    pub char: char,
+    /// How character is written in the source, e.g. "\\\"" or "&quot;"
+    pub source: String,
+}
+
+/// Escaped characters of normalized Text nodes by offsets of the nodes in the source.
+/// Renderer has to write them as they were, otherwise normalization would
+/// change them when the document is normalized again
+pub type Escapes = std::collections::HashMap<usize, Vec<Escape>>;
+
+pub fn is_enabled(check: Check, config: &TypographyConfig) -> bool {
+    match check {
+        Check::Quotes => config.quotes != QuotesStyle::Preserve,
+        Check::Dashes => config.dashes,
+        Check::Ellipsis => config.ellipsis,
+        Check::UnitSpaces => config.nbsp_before_units,
+        Check::SentenceSpaces => config.single_space_after_period,
+    }
+}
+
+/// Find what shall be replaced in the text.
+/// Previous is the last character of the content that precedes the text within the same block,
+/// it tells whether a quote at the beginning of the text is an opening one
+pub fn find(
+    text: &str,
+    previous: Option<char>,
+    check: Check,
+    config: &TypographyConfig,
+) -> Vec<Replacement> {
+    if !is_enabled(check, config) {
+        return vec![];
+    }
+    match check {
+        Check::Quotes => find_quotes(text, previous, &config.quotes),
+        Check::Dashes => find_dashes(text, previous),
+        Check::Ellipsis => find_runs(text, '.')
+            .filter(|run| run.len() == 3)
+            .map(|range| Replacement {
+                range,
+                replacement: "…",
+            })
+            .collect(),
+        Check::UnitSpaces => find_unit_spaces(text),
+        Check::SentenceSpaces => find_sentence_spaces(text),
+    }
+}
+
+/// Apply replacements that do not overlap each other
+pub fn apply(text: &str, replacements: &[Replacement]) -> String {
+    let mut result = text.to_string();
+    for replacement in replacements.iter().rev() {
+        result.replace_range(replacement.range.clone(), replacement.replacement);
+    }
+    result
+}
+
+/// Apply every enabled normalization to the text
+pub fn normalize_text(text: &str, previous: Option<char>, config: &TypographyConfig) -> String {
+    [
+        Check::Dashes,
+        Check::Ellipsis,
+        Check::Quotes,
+        Check::UnitSpaces,
+        Check::SentenceSpaces,
+    ]
+    .iter()
+    .fold(text.to_string(), |text, check| {
+        apply(&text, &find(&text, previous, *check, config))
+    })
+}
+
+/// Normalize every Text node of the document in-place.
+/// Source is the document the AST is parsed from, characters escaped in it are kept as they are.
+/// Returns escaped characters, so they can be written back the same way
+pub fn normalize(ast: &mut Node, source: &str, config: &TypographyConfig) -> Escapes {
+    let mut escapes = Escapes::new();
+    let mut normalized = text_nodes(ast)
+        .into_iter()
+        .map(|(text, previous)| {
+            let chars = map_to_source(text, source);
+            let mut result = String::new();
+            let mut node_escapes: Vec<Escape> = vec![];
+            let mut push_escaped = |result: &mut String, range: std::ops::Range<usize>| {
+                // Everything between segments is escaped
+                for (offset, c) in text.value[range.clone()].char_indices() {
+                    let escaped = chars
+                        .iter()
+                        .flatten()
+                        .find(|sc| sc.value == range.start + offset);
+                    if let Some(escaped) = escaped {
+                        node_escapes.push(Escape {
+                            offset: result.len(),
+                            char: c,
+                            source: source[escaped.source.clone()].to_string(),
+                        });
+                    }
+                    result.push(c);
+                }
+            };
+            let mut end = 0;
+            for (range, previous) in segments(text, chars.as_deref(), previous) {
+                push_escaped(&mut result, end..range.start);
+                result.push_str(&normalize_text(
+                    &text.value[range.clone()],
+                    previous,
+                    config,
+                ));
+                end = range.end;
+            }
+            push_escaped(&mut result, end..text.value.len());
+            if let (Some(position), false) = (&text.position, node_escapes.is_empty()) {
+                escapes.insert(position.start.offset, node_escapes);
+            }
+            result
+        })
+        .collect::<Vec<String>>()
+        .into_iter();
+    for_each_text_mut(ast, &mut |text| {
+        if let Some(value) = normalized.next() {
+            text.value = value;
+        }
+    });
+    escapes
+}
+
+/// Find parts of the text that would change after normalization.
+/// Source is the document the AST is parsed from, issues point to the characters in it
+pub fn check(ast: &Node, source: &str, check: Check, config: &TypographyConfig) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = vec![];
+    for (text, previous) in text_nodes(ast) {
+        let chars = map_to_source(text, source);
+        for (segment, previous) in segments(text, chars.as_deref(), previous) {
+            for r in find(&text.value[segment.clone()], previous, check, config) {
+                let range = segment.start + r.range.start..segment.start + r.range.end;
+                issues.push(Issue {
+                    position: to_position(text, source, chars.as_deref(), &range),
+                    original: text.value[range].to_string(),
+                    replacement: r.replacement.to_string(),
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Parts of the Text node's value between escaped characters,
+/// each with the character that precedes it
+fn segments(
+    text: &Text,
+    chars: Option<&[SourceChar]>,
+    previous: Option<char>,
+) -> Vec<(std::ops::Range<usize>, Option<char>)> {
+    let mut ranges: Vec<std::ops::Range<usize>> = vec![];
+    let mut start = 0;
+    for c in chars.unwrap_or_default().iter().filter(|c| c.escaped) {
+        ranges.push(start..c.value);
+        start = c.value
+            + text.value[c.value..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+    }
+    ranges.push(start..text.value.len());
+    ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            let before = text.value[..range.start].chars().last().or(previous);
+            (range, before)
+        })
+        .collect()
+}
+
+/// Map every character of the Text node's value to the source.
+/// Value differs from the source by backslash escapes, character references and
+/// prefixes of continuation lines, like "> " of block quotes or indentation of lists.
+/// None when the node has no position or the value can not be found in the source
+fn map_to_source(text: &Text, source: &str) -> Option<Vec<SourceChar>> {
+    let position = text.position.as_ref()?;
+    let start = position.start.offset;
+    let source = source.get(start..position.end.offset)?;
+    let mut chars: Vec<SourceChar> = vec![];
+    let mut offset = 0;
+    for (value, c) in text.value.char_indices() {
+        loop {
+            let rest = source.get(offset..)?;
+            let current = rest.chars().next()?;
+            let reference = rest
+                .find(';')
+                .filter(|end| {
+                    *end > 1
+                        && rest[1..*end]
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '#')
+                })
+                .filter(|_| current == '&' && (c != '&' || rest.starts_with("&amp;")));
+            let (len, escaped) =
+                if current == '\\' && c.is_ascii_punctuation() && rest[1..].starts_with(c) {
+                    (1 + c.len_utf8(), true)
+                } else if let Some(end) = reference {
+                    (end + 1, true)
+                } else if current == c {
+                    (c.len_utf8(), false)
+                } else {
+                    offset += current.len_utf8();
+                    continue;
+                };
+            chars.push(SourceChar {
+                value,
+                source: start + offset..start + offset + len,
+                escaped,
+            });
+            offset += len;
+            break;
+        }
+    }
+    Some(chars)
+}
+
+/// Position of the part of the Text node's value in the source.
+/// Whole node when characters could not be mapped to the source
+fn to_position(
+    text: &Text,
+    source: &str,
+    chars: Option<&[SourceChar]>,
+    range: &std::ops::Range<usize>,
+) -> Option<Position> {
+    let node = text.position.as_ref()?;
+    let mapped = chars.and_then(|chars| {
+        let first = chars.iter().find(|c| c.value == range.start)?;
+        let last = chars.iter().rev().find(|c| c.value < range.end)?;
+        Some((first.source.start, last.source.end))
+    });
+    let (start, end) = match mapped {
+        Some(mapped) => mapped,
+        None => return Some(node.clone()),
+    };
+    // Line and column(in bytes) of the offset, counted from the beginning of the node
+    let point = |offset: usize| {
+        let before = &source[node.start.offset..offset];
+        match before.rfind('\n') {
+            Some(newline) => (
+                node.start.line + before.matches('\n').count(),
+                before.len() - newline,
+            ),
+            None => (node.start.line, node.start.column + before.len()),
+        }
+    };
+    let (start_line, start_column) = point(start);
+    let (end_line, end_column) = point(end);
+    Some(Position::new(
+        start_line,
+        start_column,
+        start,
+        end_line,
+        end_column,
+        end,
+    ))
+}
+
+/// Collect Text nodes in the order of appearance together
+/// with the character that precedes each of them within the block
+fn text_nodes(ast: &Node) -> Vec<(&Text, Option<char>)> {
+    fn walk<'a>(
+        node: &'a Node,
+        previous: &mut Option<char>,
+        result: &mut Vec<(&'a Text, Option<char>)>,
+    ) {
+        match node {
+            Node::Text(text) => {
+                result.push((text, *previous));
+                *previous = text.value.chars().last().or(*previous);
+            }
+            _ if is_skipped(node) => *previous = last_char(node).or(*previous),
+            _ => {
+                if !is_phrasing(node) {
+                    *previous = None;
+                }
+                for child in node.children().into_iter().flatten() {
+                    walk(child, previous, result);
+                }
+                *previous = last_char(node).or(*previous);
+            }
+        }
+    }
+    let mut result = vec![];
+    walk(ast, &mut None, &mut result);
+    result
+}
+
+/// Visit the same Text nodes as text_nodes(), in the same order
+fn for_each_text_mut(node: &mut Node, f: &mut impl FnMut(&mut Text)) {
+    match node {
+        Node::Text(text) => f(text),
+        _ if is_skipped(node) => {}
+        _ => {
+            for child in node.children_mut().into_iter().flatten() {
+                for_each_text_mut(child, f);
+            }
+        }
+    }
+}
+
+/// Nodes which content is never normalized: URLs of auto-links
+fn is_skipped(node: &Node) -> bool {
+    match node {
+        Node::Link(link) => {
+            link.title.is_none()
+                && link.children.len() == 1
+                && match link.children.first() {
+                    Some(Node::Text(t)) => t
+                        .value
+                        .eq(link.url.strip_prefix("mailto:").unwrap_or(&link.url)),
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+/// Inline content that continues the text of the block
+fn is_phrasing(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Emphasis(_)
+            | Node::Strong(_)
+            | Node::Delete(_)
+            | Node::Link(_)
+            | Node::LinkReference(_)
+            | Node::InlineCode(_)
+            | Node::InlineMath(_)
+            | Node::Break(_)
+            | Node::Image(_)
+            | Node::ImageReference(_)
+            | Node::Html(_)
+            | Node::FootnoteReference(_)
+    )
+}
+
+/// Last character of the inline content that has no Text nodes inside
+fn last_char(node: &Node) -> Option<char> {
+    match node {
+        Node::Link(link) if is_skipped(node) => link.url.chars().last(),
+        Node::InlineCode(code) => code.value.chars().last(),
+        Node::InlineMath(math) => math.value.chars().last(),
+        Node::Image(image) => image.alt.chars().last(),
+        Node::Break(_) => Some('\n'),
+        _ => None,
+    }
+}
+
+fn is_opening_context(previous: Option<char>) -> bool {
+    match previous {
+        Some(c) => c.is_whitespace() || OPENING_CONTEXT.contains(&c),
+        None => true,
+    }
+}
+
+fn find_quotes(text: &str, previous: Option<char>, style: &QuotesStyle) -> Vec<Replacement> {
+    let mut replacements: Vec<Replacement> = vec![];
+    let mut previous = previous;
+    for (i, c) in text.char_indices() {
+        let replacement = match (style, c) {
+            (QuotesStyle::Curly, '"') if is_opening_context(previous) => Some("“"),
+            (QuotesStyle::Curly, '"') => Some("”"),
+            (QuotesStyle::Curly, '\'') if is_opening_context(previous) => Some("‘"),
+            (QuotesStyle::Curly, '\'') => Some("’"),
+            (QuotesStyle::Straight, '“' | '”' | '„') => Some("\""),
+            (QuotesStyle::Straight, '‘' | '’') => Some("'"),
+            _ => None,
+        };
+        if let Some(replacement) = replacement {
+            replacements.push(Replacement {
+                range: i..i + c.len_utf8(),
+                replacement,
+            });
+        }
+        previous = Some(c);
+    }
+    replacements
+}
+
+/// Byte ranges of runs of the same character
+fn find_runs(text: &str, run_of: char) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+    let mut runs: Vec<std::ops::Range<usize>> = vec![];
+    for (i, c) in text.char_indices() {
+        if c == run_of {
+            match runs.last_mut() {
+                Some(run) if run.end == i => run.end = i + c.len_utf8(),
+                _ => runs.push(i..i + c.len_utf8()),
+            }
+        }
+    }
+    runs.into_iter()
+}
+
+/// "--" becomes an en dash and "---" an em dash. Options of command line
+/// tools, e.g. "--help", are kept as they are
+fn find_dashes(text: &str, previous: Option<char>) -> Vec<Replacement> {
+    find_runs(text, '-')
+        .filter(|run| {
+            let before = text[..run.start].chars().last().or(match run.start {
+                0 => previous,
+                _ => None,
+            });
+            let after = text[run.end..].chars().next();
+            let is_option = before.map_or(true, char::is_whitespace)
+                && after.is_some_and(char::is_alphanumeric);
+            !is_option
+        })
+        .filter_map(|range| match range.len() {
+            2 => Some(Replacement {
+                range,
+                replacement: "–",
+            }),
+            3 => Some(Replacement {
+                range,
+                replacement: "—",
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Space between a number and a unit, e.g. "10 kg"
+fn find_unit_spaces(text: &str) -> Vec<Replacement> {
+    text.char_indices()
+        .filter(|(i, c)| {
+            *c == ' '
+                && text[..*i].ends_with(|c: char| c.is_ascii_digit())
+                && UNITS.iter().any(|unit| {
+                    text[i + 1..]
+                        .strip_prefix(unit)
+                        .is_some_and(|rest| !rest.starts_with(char::is_alphanumeric))
+                })
+        })
+        .map(|(i, _)| Replacement {
+            range: i..i + 1,
+            replacement: "\u{a0}",
+        })
+        .collect()
+}
+
+/// Multiple spaces after ".", "!" or "?" that end a sentence
+fn find_sentence_spaces(text: &str) -> Vec<Replacement> {
+    find_runs(text, ' ')
+        .filter(|run| {
+            run.len() > 1 && text[..run.start].ends_with(['.', '!', '?']) && run.end < text.len()
+        })
+        .map(|range| Replacement {
+            range,
+            replacement: " ",
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TypographyConfig {
+        TypographyConfig {
+            quotes: QuotesStyle::Curly,
+            dashes: true,
+            ellipsis: true,
+            nbsp_before_units: true,
+            single_space_after_period: true,
+        }
+    }
+
+    #[test]
+    fn text_normalized() {
+        assert_eq!(
+            "“Wait…” – she said. It’s 10\u{a0}kg, see 1–2 or use --help — or —",
+            normalize_text(
+                "\"Wait...\" -- she said.  It's 10 kg, see 1--2 or use --help --- or ---",
+                None,
+                &config()
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_straightened() {
+        let config = TypographyConfig {
+            quotes: QuotesStyle::Straight,
+            ..Default::default()
+        };
+        assert_eq!(
+            "\"Quoted\" and 'single'",
+            normalize_text("“Quoted” and ‘single’", None, &config)
+        );
+    }
+
+    #[test]
+    fn code_and_autolinks_untouched() {
+        let source = "Say \"hi\" `\"code\" -- ...` <https://a.b/c--d...>\n";
+        let mut ast = crate::ast::parse(source).unwrap();
+        normalize(&mut ast, source, &config());
+        let texts = text_nodes(&ast)
+            .iter()
+            .map(|(t, _)| t.value.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["Say “hi” ", " "], texts);
+    }
+
+    #[test]
+    fn closing_quote_after_inline_content() {
+        let source = "\"**bold**\"\n";
+        let ast = crate::ast::parse(source).unwrap();
+        assert_eq!(
+            vec!["“", "”"],
+            check(&ast, source, Check::Quotes, &config())
+                .iter()
+                .map(|i| i.replacement.as_str())
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn escaped_characters_untouched() {
+        let source = "> Say \\\"hi\\\" &quot;x&quot; \"ok\"\n> and --\n";
+        let mut ast = crate::ast::parse(source).unwrap();
+        assert_eq!(
+            vec![
+                (Position::new(1, 28, 27, 1, 29, 28), String::from("“")),
+                (Position::new(1, 31, 30, 1, 32, 31), String::from("”")),
+                (Position::new(2, 7, 38, 2, 9, 40), String::from("–")),
+            ],
+            check(&ast, source, Check::Quotes, &config())
+                .into_iter()
+                .chain(check(&ast, source, Check::Dashes, &config()))
+                .map(|i| (i.position.unwrap(), i.replacement))
+                .collect::<Vec<(Position, String)>>()
+        );
+        normalize(&mut ast, source, &config());
+        assert_eq!(
+            vec!["Say \"hi\" \"x\" “ok”\nand –"],
+            text_nodes(&ast)
+                .iter()
+                .map(|(t, _)| t.value.clone())
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn single_letter_units_untouched() {
+        assert_eq!(
+            "Wait 5 s or 10\u{a0}ms, 3 t of 5\u{a0}%",
+            normalize_text("Wait 5 s or 10 ms, 3 t of 5 %", None, &config())
+        );
+    }
+}